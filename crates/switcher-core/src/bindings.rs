@@ -0,0 +1,351 @@
+//! Mouse bindings of workspace buttons: which action a click runs, keyed by
+//! the mouse button and the modifiers held.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::provider::WorkspaceAction;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+}
+
+/// A click on a workspace button, e.g. `ctrl+left` or `double+left`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MouseTrigger {
+    pub button: MouseButton,
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub double_click: bool,
+}
+
+impl MouseTrigger {
+    pub const fn new(button: MouseButton) -> Self {
+        Self {
+            button,
+            shift: false,
+            ctrl: false,
+            alt: false,
+            double_click: false,
+        }
+    }
+
+    pub const fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub const fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub const fn double_click(mut self) -> Self {
+        self.double_click = true;
+        self
+    }
+
+    /// The click `response` received this frame, if any. A double click is
+    /// also reported as a single click on the frame of its first click.
+    pub fn from_response(response: &egui::Response) -> Option<Self> {
+        let (button, pointer_button) = if response.clicked() {
+            (MouseButton::Left, egui::PointerButton::Primary)
+        } else if response.clicked_by(egui::PointerButton::Middle) {
+            (MouseButton::Middle, egui::PointerButton::Middle)
+        } else {
+            return None;
+        };
+
+        let modifiers = response.ctx.input(|i| i.modifiers);
+        Some(Self {
+            button,
+            shift: modifiers.shift,
+            ctrl: modifiers.ctrl,
+            alt: modifiers.alt,
+            double_click: response.double_clicked_by(pointer_button),
+        })
+    }
+}
+
+impl FromStr for MouseTrigger {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split('+').map(str::trim).collect::<Vec<_>>();
+        let button = match tokens.pop().map(str::to_ascii_lowercase).as_deref() {
+            Some("left") => MouseButton::Left,
+            Some("middle") => MouseButton::Middle,
+            _ => anyhow::bail!("Invalid mouse binding `{s}`, it must end with `left` or `middle`"),
+        };
+
+        let mut trigger = Self::new(button);
+        for token in tokens {
+            match token.to_ascii_lowercase().as_str() {
+                "shift" => trigger.shift = true,
+                "ctrl" => trigger.ctrl = true,
+                "alt" => trigger.alt = true,
+                "double" => trigger.double_click = true,
+                _ => anyhow::bail!(
+                    "Invalid mouse binding `{s}`, unknown modifier `{token}`, expected `shift`, `ctrl`, `alt` or `double`"
+                ),
+            }
+        }
+
+        Ok(trigger)
+    }
+}
+
+impl fmt::Display for MouseTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = [
+            (self.shift, "shift+"),
+            (self.ctrl, "ctrl+"),
+            (self.alt, "alt+"),
+            (self.double_click, "double+"),
+        ];
+        for (_, name) in modifiers.iter().filter(|(held, _)| *held) {
+            f.write_str(name)?;
+        }
+
+        match self.button {
+            MouseButton::Left => f.write_str("left"),
+            MouseButton::Middle => f.write_str("middle"),
+        }
+    }
+}
+
+/// The action each click on a workspace button runs.
+///
+/// In the config file it is a table of clicks and action names, applied over
+/// the defaults like [`MouseBindings::apply`].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "BTreeMap<String, String>")]
+pub struct MouseBindings {
+    bindings: Vec<(MouseTrigger, WorkspaceAction)>,
+}
+
+impl Default for MouseBindings {
+    fn default() -> Self {
+        use MouseButton::*;
+
+        Self {
+            bindings: vec![
+                (MouseTrigger::new(Left), WorkspaceAction::Focus),
+                (
+                    MouseTrigger::new(Middle),
+                    WorkspaceAction::MoveFocusedWindowHere,
+                ),
+                (
+                    MouseTrigger::new(Left).shift(),
+                    WorkspaceAction::MoveFocusedWindowAndFollow,
+                ),
+            ],
+        }
+    }
+}
+
+impl MouseBindings {
+    pub fn action(&self, trigger: MouseTrigger) -> Option<WorkspaceAction> {
+        self.bindings
+            .iter()
+            .find(|(t, _)| *t == trigger)
+            .map(|(_, action)| *action)
+    }
+
+    /// Binds `trigger` to `action`, or unbinds it when `action` is `None`.
+    pub fn set(&mut self, trigger: MouseTrigger, action: Option<WorkspaceAction>) {
+        self.bindings.retain(|(t, _)| *t != trigger);
+        if let Some(action) = action {
+            self.bindings.push((trigger, action));
+        }
+    }
+
+    /// Applies bindings like `middle=close-windows, ctrl+left=none`, where
+    /// `none` unbinds a click.
+    pub fn apply(&mut self, spec: &str) -> anyhow::Result<()> {
+        for binding in spec.split(',').map(str::trim).filter(|b| !b.is_empty()) {
+            let (trigger, action) = binding.split_once('=').with_context(|| {
+                format!("Invalid mouse binding `{binding}`, expected `<click>=<action>`")
+            })?;
+
+            self.set_named(trigger, action)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_named(&mut self, trigger: &str, action: &str) -> anyhow::Result<()> {
+        let trigger = trigger.trim().parse()?;
+        let action = match action.trim() {
+            "none" => None,
+            action => Some(action.parse()?),
+        };
+        self.set(trigger, action);
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (MouseTrigger, WorkspaceAction)> + '_ {
+        self.bindings.iter().copied()
+    }
+}
+
+impl TryFrom<BTreeMap<String, String>> for MouseBindings {
+    type Error = anyhow::Error;
+
+    fn try_from(table: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let mut bindings = Self::default();
+        for (trigger, action) in &table {
+            bindings.set_named(trigger, action)?;
+        }
+        Ok(bindings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{Event, Modifiers, PointerButton, Pos2, RawInput, Rect, Sense};
+
+    use super::*;
+
+    #[test]
+    fn parses_triggers() {
+        let trigger = |s: &str| s.parse::<MouseTrigger>().unwrap();
+
+        assert_eq!(trigger("middle"), MouseTrigger::new(MouseButton::Middle));
+        assert_eq!(
+            trigger("Ctrl + Left"),
+            MouseTrigger::new(MouseButton::Left).ctrl()
+        );
+        assert_eq!(
+            trigger("shift+double+left").to_string(),
+            "shift+double+left"
+        );
+        assert!("right".parse::<MouseTrigger>().is_err());
+        assert!("meta+left".parse::<MouseTrigger>().is_err());
+    }
+
+    #[test]
+    fn applies_bindings_over_defaults() {
+        let mut bindings = MouseBindings::default();
+        bindings
+            .apply("middle=close-windows, ctrl+left=move-window-here, shift+left=none")
+            .unwrap();
+
+        let action = |s: &str| bindings.action(s.parse().unwrap());
+        assert_eq!(action("left"), Some(WorkspaceAction::Focus));
+        assert_eq!(action("middle"), Some(WorkspaceAction::CloseAllWindows));
+        assert_eq!(
+            action("ctrl+left"),
+            Some(WorkspaceAction::MoveFocusedWindowHere)
+        );
+        assert_eq!(action("shift+left"), None);
+
+        let error = bindings.apply("left=explode").unwrap_err().to_string();
+        assert!(error.contains("move-window-and-follow"), "{error}");
+    }
+
+    /// Runs a headless frame with a button for each list of events, returning
+    /// the triggers the button reported. Modifiers are held for the frame of
+    /// a pointer button event.
+    fn click(frames: Vec<Vec<Event>>) -> Vec<MouseTrigger> {
+        let ctx = egui::Context::default();
+        let rect = Rect::from_min_max(Pos2::ZERO, Pos2::new(40.0, 28.0));
+
+        frames
+            .into_iter()
+            .enumerate()
+            .filter_map(|(frame, events)| {
+                let modifiers = events
+                    .iter()
+                    .find_map(|e| match e {
+                        Event::PointerButton { modifiers, .. } => Some(*modifiers),
+                        _ => None,
+                    })
+                    .unwrap_or_default();
+                let input = RawInput {
+                    screen_rect: Some(Rect::from_min_max(Pos2::ZERO, Pos2::new(200.0, 40.0))),
+                    time: Some(frame as f64 * 0.05),
+                    modifiers,
+                    events,
+                    ..Default::default()
+                };
+
+                let mut trigger = None;
+                let _ = ctx.run(input, |ctx| {
+                    egui::CentralPanel::default().show(ctx, |ui| {
+                        let response = ui.interact(rect, egui::Id::new("1"), Sense::click());
+                        trigger = MouseTrigger::from_response(&response);
+                    });
+                });
+                trigger
+            })
+            .collect()
+    }
+
+    fn button(button: PointerButton, pressed: bool, modifiers: Modifiers) -> Event {
+        Event::PointerButton {
+            pos: Pos2::new(10.0, 10.0),
+            button,
+            pressed,
+            modifiers,
+        }
+    }
+
+    #[test]
+    fn reads_clicks_from_responses() {
+        let hover = || vec![Event::PointerMoved(Pos2::new(10.0, 10.0))];
+        let press = |b, m| vec![button(b, true, m)];
+        let release = |b, m| vec![button(b, false, m)];
+
+        let triggers = click(vec![
+            hover(),
+            press(PointerButton::Middle, Modifiers::NONE),
+            release(PointerButton::Middle, Modifiers::NONE),
+        ]);
+        assert_eq!(triggers, [MouseTrigger::new(MouseButton::Middle)]);
+
+        let triggers = click(vec![
+            hover(),
+            press(PointerButton::Primary, Modifiers::CTRL),
+            release(PointerButton::Primary, Modifiers::CTRL),
+        ]);
+        assert_eq!(triggers, [MouseTrigger::new(MouseButton::Left).ctrl()]);
+
+        let triggers = click(vec![
+            hover(),
+            press(PointerButton::Primary, Modifiers::NONE),
+            release(PointerButton::Primary, Modifiers::NONE),
+            press(PointerButton::Primary, Modifiers::NONE),
+            release(PointerButton::Primary, Modifiers::NONE),
+        ]);
+        assert_eq!(
+            triggers,
+            [
+                MouseTrigger::new(MouseButton::Left),
+                MouseTrigger::new(MouseButton::Left).double_click(),
+            ]
+        );
+
+        let triggers = click(vec![
+            hover(),
+            press(PointerButton::Middle, Modifiers::NONE),
+            release(PointerButton::Middle, Modifiers::NONE),
+            press(PointerButton::Middle, Modifiers::NONE),
+            release(PointerButton::Middle, Modifiers::NONE),
+        ]);
+        assert_eq!(
+            triggers,
+            [
+                MouseTrigger::new(MouseButton::Middle),
+                MouseTrigger::new(MouseButton::Middle).double_click(),
+            ]
+        );
+    }
+}
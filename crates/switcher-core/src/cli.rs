@@ -0,0 +1,191 @@
+//! Command line of the switcher. Without a subcommand the switcher starts as
+//! usual, the subcommands print to the console and exit.
+//!
+//! The flags live here rather than in the Windows-only binary because they
+//! override the [`Options`] loaded here, and so their parsing is tested on
+//! every platform. clap itself is platform independent.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use tracing::level_filters::LevelFilter;
+
+use crate::options::Options;
+use crate::provider::ProviderKind;
+
+#[derive(Clone, Debug, Parser)]
+#[command(
+    name = "glazewm-switcher",
+    about = "A workspace switcher in the Windows taskbar for GlazeWM and komorebi",
+    disable_version_flag = true
+)]
+pub struct Cli {
+    /// Config file to use instead of the one in the user config directory.
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    /// Log level, e.g. `debug`. Overrides `SWITCHER_LOG`.
+    #[arg(long, global = true, value_name = "LEVEL")]
+    pub log_level: Option<LevelFilter>,
+    /// Window manager backend: `auto`, `glazewm`, `komorebi` or `external`.
+    /// Overrides the config file and `SWITCHER_PROVIDER`.
+    #[arg(long, global = true, value_name = "PROVIDER")]
+    pub provider: Option<ProviderKind>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// Print the window manager state as JSON and exit.
+    DumpState,
+    /// Validate the config file and connect to the window manager.
+    Check,
+    /// Print the version and exit.
+    Version,
+}
+
+impl Cli {
+    /// The `--config` path, or the default config path.
+    pub fn config_path(&self) -> anyhow::Result<PathBuf> {
+        match &self.config {
+            Some(path) => Ok(path.clone()),
+            None => Options::default_path().context("Failed to find the user config directory"),
+        }
+    }
+
+    /// Loads the options from [`Cli::config_path`], with the command line
+    /// overrides applied.
+    pub fn options(&self) -> anyhow::Result<Options> {
+        let mut options = Options::load(&self.config_path()?)?;
+        self.apply_overrides(&mut options);
+        Ok(options)
+    }
+
+    /// Applies the command line flags over options loaded from the config file.
+    pub fn apply_overrides(&self, options: &mut Options) {
+        if let Some(provider) = self.provider {
+            options.provider = provider;
+        }
+    }
+}
+
+/// Reads the window manager state once and formats it as pretty JSON.
+pub fn dump_state(options: &Options) -> anyhow::Result<String> {
+    let provider = crate::provider::create(options)?;
+    let state = provider
+        .snapshot()
+        .with_context(|| format!("Failed to read {} state", provider.name()))?;
+
+    Ok(serde_json::to_string_pretty(&state)?)
+}
+
+/// Connects to the window manager picked by `options`, loaded from
+/// `config_path`, and describes what was found.
+pub fn check(config_path: &Path, options: &Options) -> anyhow::Result<String> {
+    let mut report = if config_path.exists() {
+        format!("Config: {}\n", config_path.display())
+    } else {
+        format!(
+            "Config: {} (not found, using the defaults)\n",
+            config_path.display()
+        )
+    };
+
+    let provider = crate::provider::create(options)?;
+    let state = provider
+        .snapshot()
+        .with_context(|| format!("Failed to connect to {}", provider.name()))?;
+
+    let workspaces = state
+        .monitors
+        .iter()
+        .map(|m| m.workspaces.len())
+        .sum::<usize>();
+    report += &format!(
+        "Connected to {}: {} monitors, {workspaces} workspaces",
+        provider.name(),
+        state.monitors.len()
+    );
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_flags_and_subcommands() {
+        let cli = Cli::try_parse_from(["glazewm-switcher"]).unwrap();
+        assert_eq!(cli.command, None);
+        assert_eq!(cli.provider, None);
+
+        let cli = Cli::try_parse_from([
+            "glazewm-switcher",
+            "dump-state",
+            "--provider",
+            "komorebi",
+            "--log-level",
+            "trace",
+            "--config",
+            "switcher.toml",
+        ])
+        .unwrap();
+        assert_eq!(cli.command, Some(Command::DumpState));
+        assert_eq!(cli.provider, Some(ProviderKind::Komorebi));
+        assert_eq!(cli.log_level, Some(LevelFilter::TRACE));
+        assert_eq!(cli.config_path().unwrap(), Path::new("switcher.toml"));
+
+        let error = Cli::try_parse_from(["glazewm-switcher", "--provider", "i3"])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Unknown provider `i3`"), "{error}");
+        assert!(Cli::try_parse_from(["glazewm-switcher", "restart"]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn checks_and_dumps_external_state() {
+        let dir = std::env::temp_dir().join(format!("switcher-cli-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(
+            &path,
+            "provider = \"glazewm\"\nexternal_command = \"echo *3\"",
+        )
+        .unwrap();
+
+        let cli = Cli::try_parse_from([
+            "glazewm-switcher",
+            "--config",
+            path.to_str().unwrap(),
+            "--provider",
+            "external",
+            "check",
+        ])
+        .unwrap();
+        let options = cli.options().unwrap();
+        assert_eq!(options.provider, ProviderKind::External);
+
+        let report = check(&path, &options).unwrap();
+        assert!(
+            report.ends_with("Connected to External: 1 monitors, 1 workspaces"),
+            "{report}"
+        );
+
+        let state: serde_json::Value =
+            serde_json::from_str(&dump_state(&options).unwrap()).unwrap();
+        assert_eq!(state["monitors"][0]["workspaces"][0]["name"], "3");
+        assert_eq!(state["monitors"][0]["workspaces"][0]["focused"], true);
+
+        let failing = Options {
+            external_command: Some("false".into()),
+            ..options
+        };
+        let error = format!("{:#}", check(&path, &failing).unwrap_err());
+        assert!(error.contains("Failed to connect to External"), "{error}");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
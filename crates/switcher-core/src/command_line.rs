@@ -0,0 +1,82 @@
+//! Splitting of configured command lines into arguments, shared by the
+//! backends that run commands.
+
+/// Splits a command line into its arguments like a shell would. Text in
+/// double quotes may escape `"` and `\` with a backslash, other backslashes
+/// are kept so Windows paths need no escaping. Text in single quotes is taken
+/// as is.
+pub fn split_args(command_line: &str) -> anyhow::Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut has_arg = false;
+    let mut chars = command_line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                has_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => current.push(c),
+                            Some(c) => current.extend(['\\', c]),
+                            None => anyhow::bail!("Unclosed `\"` in `{command_line}`"),
+                        },
+                        Some(c) => current.push(c),
+                        None => anyhow::bail!("Unclosed `\"` in `{command_line}`"),
+                    }
+                }
+            }
+            '\'' => {
+                has_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => anyhow::bail!("Unclosed `'` in `{command_line}`"),
+                    }
+                }
+            }
+            c if c.is_whitespace() => {
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+
+    if has_arg {
+        args.push(current);
+    }
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_quoted_arguments() {
+        assert_eq!(
+            split_args("focus  --workspace 'a b'").unwrap(),
+            ["focus", "--workspace", "a b"]
+        );
+        assert_eq!(
+            split_args(r#""C:\Program Files\wm.exe" --name "say \"hi\"" """#).unwrap(),
+            [r"C:\Program Files\wm.exe", "--name", r#"say "hi""#, ""]
+        );
+    }
+
+    #[test]
+    fn unclosed_quote_is_an_error() {
+        assert!(split_args(r#"focus --workspace "web"#).is_err());
+        assert!(split_args("focus --workspace 'web").is_err());
+    }
+}
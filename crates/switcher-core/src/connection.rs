@@ -0,0 +1,148 @@
+//! Connection status of a provider and the delay between reconnect attempts.
+
+use std::time::Duration;
+
+/// Consecutive failed attempts after which a provider is reported offline.
+const OFFLINE_AFTER: u32 = 3;
+
+/// Whether the switcher can currently talk to the window manager.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// No connection was made yet since the switcher started.
+    #[default]
+    Connecting,
+    Connected,
+    /// The connection was lost and is being retried.
+    Reconnecting,
+    /// Several attempts in a row failed. Retries continue at the slowest rate.
+    Offline,
+}
+
+impl ConnectionState {
+    pub fn is_connected(self) -> bool {
+        self == Self::Connected
+    }
+
+    /// Status text for the window manager named `provider`, e.g.
+    /// `Reconnecting to GlazeWM…`.
+    pub fn describe(self, provider: &str) -> String {
+        match self {
+            Self::Connecting => format!("Connecting to {provider}…"),
+            Self::Connected => format!("Connected to {provider}"),
+            Self::Reconnecting => format!("Reconnecting to {provider}…"),
+            Self::Offline => format!("{provider} Offline"),
+        }
+    }
+}
+
+/// Follows the outcome of connection attempts.
+#[derive(Debug, Default)]
+pub struct ConnectionTracker {
+    state: ConnectionState,
+    failures: u32,
+}
+
+impl ConnectionTracker {
+    pub fn state(&self) -> ConnectionState {
+        self.state
+    }
+
+    /// Records a working connection. Returns the new state if it changed.
+    pub fn connected(&mut self) -> Option<ConnectionState> {
+        self.failures = 0;
+        self.set(ConnectionState::Connected)
+    }
+
+    /// Records a failed or lost connection. Returns the new state if it changed.
+    pub fn failed(&mut self) -> Option<ConnectionState> {
+        self.failures = self.failures.saturating_add(1);
+
+        let state = match self.state {
+            _ if self.failures >= OFFLINE_AFTER => ConnectionState::Offline,
+            ConnectionState::Connecting => ConnectionState::Connecting,
+            _ => ConnectionState::Reconnecting,
+        };
+
+        self.set(state)
+    }
+
+    fn set(&mut self, state: ConnectionState) -> Option<ConnectionState> {
+        if self.state == state {
+            return None;
+        }
+
+        self.state = state;
+        Some(state)
+    }
+}
+
+/// Exponentially growing delay between reconnect attempts.
+#[derive(Clone, Debug)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    attempt: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(500), Duration::from_secs(30))
+    }
+}
+
+impl Backoff {
+    pub const fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            attempt: 0,
+        }
+    }
+
+    /// Delay to wait before the next attempt, doubling on every call up to `max`.
+    pub fn next_delay(&mut self) -> Duration {
+        let factor = 1u32 << self.attempt.min(16);
+        self.attempt = self.attempt.saturating_add(1);
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+
+    /// Starts over from the initial delay, after a successful connection.
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(5));
+        let delays = (0..5)
+            .map(|_| backoff.next_delay().as_secs())
+            .collect::<Vec<_>>();
+        assert_eq!(delays, [1, 2, 4, 5, 5]);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn tracks_lost_and_restored_connections() {
+        use ConnectionState::*;
+
+        let mut tracker = ConnectionTracker::default();
+        assert_eq!(tracker.failed(), None);
+        assert_eq!(tracker.connected(), Some(Connected));
+        assert_eq!(tracker.connected(), None);
+
+        assert_eq!(tracker.failed(), Some(Reconnecting));
+        assert_eq!(tracker.failed(), None);
+        assert_eq!(tracker.failed(), Some(Offline));
+        assert_eq!(tracker.failed(), None);
+
+        assert_eq!(tracker.connected(), Some(Connected));
+        assert_eq!(tracker.failed(), Some(Reconnecting));
+    }
+}
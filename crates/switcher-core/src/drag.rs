@@ -0,0 +1,208 @@
+//! Drags that end on or start from a workspace button: a window moved by its
+//! title bar and dropped on a button, or a button dragged out of its switcher.
+
+use egui::{Pos2, Response, Ui};
+
+/// What a finished drag asks for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DropAction {
+    /// The focused window was released over the button of `workspace`.
+    FocusedWindow { workspace: String },
+    /// The button of `workspace` was released outside its switcher, at `pos`
+    /// in points relative to the switcher window.
+    Workspace { workspace: String, pos: Pos2 },
+}
+
+/// Follows drags across the frames of one switcher.
+///
+/// Call [`DragState::begin_frame`] before showing the buttons,
+/// [`DragState::button`] for each of them and [`DragState::end_frame`] after.
+#[derive(Debug, Default)]
+pub struct DragState {
+    /// Pointer position while a window is dragged by its title bar. The
+    /// switcher gets no pointer input then, so it is fed from outside.
+    window_pointer: Option<Pos2>,
+    /// Button under `window_pointer` in the last frame.
+    target: Option<String>,
+    next_target: Option<String>,
+    /// Workspace button released outside the switcher, not yet taken.
+    drop: Option<DropAction>,
+}
+
+impl DragState {
+    /// Whether a window is being dragged, so the pointer must be fed with
+    /// [`DragState::window_moved`] every frame.
+    pub fn is_window_dragged(&self) -> bool {
+        self.window_pointer.is_some()
+    }
+
+    /// Starts or follows a window drag, with the pointer at `pos` in points
+    /// relative to the switcher window.
+    pub fn window_moved(&mut self, pos: Pos2) {
+        self.window_pointer = Some(pos);
+    }
+
+    /// Ends a window drag, dropping the window on the button it was released over.
+    pub fn window_released(&mut self) -> Option<DropAction> {
+        self.window_pointer.take()?;
+        let workspace = self.target.take()?;
+        Some(DropAction::FocusedWindow { workspace })
+    }
+
+    /// The button a dragged window was over in the last frame, to highlight it.
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    pub fn begin_frame(&mut self) {
+        self.next_target = None;
+    }
+
+    pub fn end_frame(&mut self) {
+        self.target = self.next_target.take();
+    }
+
+    /// Follows the button of `workspace`, which must sense drags.
+    pub fn button(&mut self, ui: &Ui, response: &Response, workspace: &str) {
+        if response.drag_stopped() {
+            let pos = response
+                .interact_pointer_pos()
+                .or_else(|| ui.input(|i| i.pointer.latest_pos()));
+            if let Some(pos) = pos.filter(|pos| !ui.ctx().screen_rect().contains(*pos)) {
+                self.drop = Some(DropAction::Workspace {
+                    workspace: workspace.to_string(),
+                    pos,
+                });
+            }
+        }
+
+        if self
+            .window_pointer
+            .is_some_and(|pos| response.rect.contains(pos))
+        {
+            self.next_target = Some(workspace.to_string());
+        }
+    }
+
+    /// The workspace button dropped outside the switcher, if any.
+    pub fn take_drop(&mut self) -> Option<DropAction> {
+        self.drop.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{vec2, Event, PointerButton, RawInput, Rect, Sense};
+
+    use super::*;
+
+    const SCREEN: Rect = Rect::from_min_max(Pos2::ZERO, Pos2::new(200.0, 40.0));
+
+    fn button_rect(workspace: &str) -> Rect {
+        let x = if workspace == "1" { 0.0 } else { 50.0 };
+        Rect::from_min_size(Pos2::new(x, 0.0), vec2(40.0, 28.0))
+    }
+
+    /// Runs one frame of a switcher with the buttons `1` and `2`, returning
+    /// the button to highlight in the next frame.
+    fn frame(ctx: &egui::Context, drag: &mut DragState, events: Vec<Event>) -> Option<String> {
+        let input = RawInput {
+            screen_rect: Some(SCREEN),
+            events,
+            ..Default::default()
+        };
+
+        let _ = ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                drag.begin_frame();
+                for workspace in ["1", "2"] {
+                    let id = egui::Id::new(workspace);
+                    let response = ui.interact(button_rect(workspace), id, Sense::click_and_drag());
+                    drag.button(ui, &response, workspace);
+                }
+                drag.end_frame();
+            });
+        });
+
+        drag.target().map(String::from)
+    }
+
+    fn pointer_button(pos: Pos2, pressed: bool) -> Event {
+        Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers: Default::default(),
+        }
+    }
+
+    #[test]
+    fn dragged_window_drops_on_highlighted_button() {
+        let ctx = egui::Context::default();
+        let mut drag = DragState::default();
+        frame(&ctx, &mut drag, vec![]);
+
+        drag.window_moved(Pos2::new(60.0, 10.0));
+        assert_eq!(frame(&ctx, &mut drag, vec![]).as_deref(), Some("2"));
+
+        drag.window_moved(Pos2::new(10.0, 10.0));
+        assert_eq!(frame(&ctx, &mut drag, vec![]).as_deref(), Some("1"));
+
+        assert_eq!(
+            drag.window_released(),
+            Some(DropAction::FocusedWindow {
+                workspace: "1".into()
+            })
+        );
+        assert!(!drag.is_window_dragged());
+        assert_eq!(frame(&ctx, &mut drag, vec![]), None);
+    }
+
+    #[test]
+    fn dragged_window_released_elsewhere_drops_nothing() {
+        let ctx = egui::Context::default();
+        let mut drag = DragState::default();
+
+        drag.window_moved(Pos2::new(45.0, 10.0));
+        assert_eq!(frame(&ctx, &mut drag, vec![]), None);
+        assert_eq!(drag.window_released(), None);
+    }
+
+    #[test]
+    fn button_dragged_out_of_switcher_drops_workspace() {
+        let ctx = egui::Context::default();
+        let mut drag = DragState::default();
+
+        let start = Pos2::new(60.0, 10.0);
+        let end = Pos2::new(320.0, 10.0);
+        frame(&ctx, &mut drag, vec![Event::PointerMoved(start)]);
+        frame(&ctx, &mut drag, vec![pointer_button(start, true)]);
+        frame(&ctx, &mut drag, vec![Event::PointerMoved(end)]);
+        assert_eq!(drag.take_drop(), None);
+
+        frame(&ctx, &mut drag, vec![pointer_button(end, false)]);
+        assert_eq!(
+            drag.take_drop(),
+            Some(DropAction::Workspace {
+                workspace: "2".into(),
+                pos: end
+            })
+        );
+        assert_eq!(drag.take_drop(), None);
+    }
+
+    #[test]
+    fn button_dropped_inside_switcher_is_ignored() {
+        let ctx = egui::Context::default();
+        let mut drag = DragState::default();
+
+        let start = Pos2::new(10.0, 10.0);
+        let end = Pos2::new(60.0, 10.0);
+        frame(&ctx, &mut drag, vec![Event::PointerMoved(start)]);
+        frame(&ctx, &mut drag, vec![pointer_button(start, true)]);
+        frame(&ctx, &mut drag, vec![Event::PointerMoved(end)]);
+        frame(&ctx, &mut drag, vec![pointer_button(end, false)]);
+
+        assert_eq!(drag.take_drop(), None);
+    }
+}
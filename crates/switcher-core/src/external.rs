@@ -0,0 +1,293 @@
+//! A backend driven by a user-configured command, for window managers the
+//! switcher doesn't know about.
+//!
+//! The command prints snapshots to stdout, either as one JSON [`State`] per
+//! line or as plain workspace names, one per line, ended by an empty line.
+//! A command that exits is started again, so a script printing a single
+//! snapshot works as a poller.
+
+use std::io::{BufRead, BufReader};
+use std::ops::ControlFlow;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+use anyhow::Context;
+
+use crate::command_line::split_args;
+use crate::provider::WorkspaceProvider;
+use crate::state::State;
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Delay before starting the command again after it exited.
+const RESTART_DELAY: Duration = Duration::from_secs(2);
+
+/// Monitor id given to plain text snapshots.
+const PLAIN_MONITOR_ID: &str = "external-default";
+
+pub struct External {
+    /// Command line printing snapshots.
+    command: String,
+    /// Command line template run to activate a workspace, see [`render_template`].
+    activate_command: Option<String>,
+}
+
+impl External {
+    pub fn new(command: String, activate_command: Option<String>) -> Self {
+        Self {
+            command,
+            activate_command,
+        }
+    }
+
+    fn spawn(&self) -> anyhow::Result<Child> {
+        let args = split_args(&self.command)?;
+        let (program, args) = args
+            .split_first()
+            .context("The external command is empty")?;
+
+        let mut cmd = Command::new(program);
+        cmd.args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null());
+        #[cfg(windows)]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+
+        cmd.spawn()
+            .with_context(|| format!("Failed to run `{}`", self.command))
+    }
+}
+
+impl WorkspaceProvider for External {
+    fn name(&self) -> &str {
+        "External"
+    }
+
+    /// Runs the command until it printed its first snapshot.
+    fn snapshot(&self) -> anyhow::Result<State> {
+        let mut child = self.spawn()?;
+        let stdout = child.stdout.take().context("No stdout")?;
+
+        let mut first = None;
+        let result = read_snapshots(BufReader::new(stdout), |state| {
+            first = Some(state);
+            ControlFlow::Break(())
+        });
+
+        let _ = child.kill();
+        let _ = child.wait();
+        result?;
+
+        first.with_context(|| format!("`{}` printed no snapshot", self.command))
+    }
+
+    /// Runs the command again each time it exits successfully after printing
+    /// a snapshot. Fails once it can't be started, fails or prints nothing.
+    fn watch(&self, sink: &mut dyn FnMut(State)) -> anyhow::Result<()> {
+        loop {
+            let mut child = self.spawn()?;
+            let stdout = child.stdout.take().context("No stdout")?;
+
+            let mut printed = false;
+            let result = read_snapshots(BufReader::new(stdout), |state| {
+                printed = true;
+                sink(state);
+                ControlFlow::Continue(())
+            });
+            let status = child.wait()?;
+            tracing::debug!(target: "external", "`{}` exited with {status}", self.command);
+            result?;
+
+            if !status.success() {
+                anyhow::bail!("`{}` exited with {status}", self.command);
+            }
+            if !printed {
+                anyhow::bail!("`{}` printed no snapshot", self.command);
+            }
+
+            std::thread::sleep(RESTART_DELAY);
+        }
+    }
+
+    fn activate_workspace(
+        &self,
+        monitor_idx: usize,
+        workspace_name: &str,
+        focus_monitor: bool,
+    ) -> anyhow::Result<()> {
+        let template = self
+            .activate_command
+            .as_deref()
+            .context("No command is configured to activate workspaces")?;
+
+        let args = render_template(template, monitor_idx, workspace_name, focus_monitor)?;
+        let (program, args) = args
+            .split_first()
+            .context("The activate command is empty")?;
+
+        let mut cmd = Command::new(program);
+        cmd.args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        #[cfg(windows)]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+
+        let status = cmd
+            .status()
+            .with_context(|| format!("Failed to run `{template}`"))?;
+        anyhow::ensure!(status.success(), "`{template}` failed with {status}");
+
+        Ok(())
+    }
+
+    fn run_command(&self, command: &str) -> anyhow::Result<()> {
+        anyhow::bail!("The external provider can't run `{command}`")
+    }
+}
+
+/// Reads snapshots from `reader` until it ends or `on_state` breaks.
+fn read_snapshots(
+    reader: impl BufRead,
+    mut on_state: impl FnMut(State) -> ControlFlow<()>,
+) -> anyhow::Result<()> {
+    let mut plain = String::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        let trimmed = line.trim();
+
+        let state = if trimmed.starts_with('{') {
+            State::from_json(trimmed)?
+        } else if trimmed.is_empty() {
+            if plain.is_empty() {
+                continue;
+            }
+            let state = State::from_plain_text(&plain, PLAIN_MONITOR_ID)?;
+            plain.clear();
+            state
+        } else {
+            plain.push_str(trimmed);
+            plain.push('\n');
+            continue;
+        };
+
+        if on_state(state).is_break() {
+            return Ok(());
+        }
+    }
+
+    // the last plain snapshot may end with the output instead of an empty line
+    if !plain.is_empty() {
+        let _ = on_state(State::from_plain_text(&plain, PLAIN_MONITOR_ID)?);
+    }
+
+    Ok(())
+}
+
+/// Splits `template` into arguments and replaces `{workspace}`, `{monitor}`
+/// and `{focus_monitor}` in each of them, so a workspace name with spaces
+/// stays a single argument.
+fn render_template(
+    template: &str,
+    monitor_idx: usize,
+    workspace_name: &str,
+    focus_monitor: bool,
+) -> anyhow::Result<Vec<String>> {
+    let args = split_args(template)?
+        .into_iter()
+        .map(|arg| {
+            arg.replace("{workspace}", workspace_name)
+                .replace("{monitor}", &monitor_idx.to_string())
+                .replace("{focus_monitor}", &focus_monitor.to_string())
+        })
+        .collect();
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn reads_json_and_plain_snapshots() {
+        let output = "\
+{\"monitors\": [{\"name\": \"main\", \"workspaces\": [{\"name\": \"web\", \"focused\": true}]}]}
+1
+*2
+
+3
+> 4
+";
+        let mut states = Vec::new();
+        read_snapshots(Cursor::new(output), |state| {
+            states.push(state);
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+
+        let focused = states
+            .iter()
+            .map(|s| {
+                let monitor = &s.monitors[0];
+                let workspace = monitor.workspaces.iter().find(|w| w.focused).unwrap();
+                (monitor.id.as_str(), workspace.name.as_str())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            focused,
+            [
+                ("main", "web"),
+                (PLAIN_MONITOR_ID, "2"),
+                (PLAIN_MONITOR_ID, "4")
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_reading_on_break() {
+        let mut count = 0;
+        read_snapshots(Cursor::new("1\n\n2\n\n"), |_| {
+            count += 1;
+            ControlFlow::Break(())
+        })
+        .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn malformed_json_is_an_error() {
+        let result = read_snapshots(Cursor::new("{\"monitors\": 1}\n"), |_| {
+            ControlFlow::Continue(())
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn renders_template_per_argument() {
+        let args = render_template(
+            r#""C:\Program Files\wm\wmctl.exe" focus --monitor {monitor} --workspace {workspace}"#,
+            1,
+            "web dev",
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            args,
+            [
+                r"C:\Program Files\wm\wmctl.exe",
+                "focus",
+                "--monitor",
+                "1",
+                "--workspace",
+                "web dev"
+            ]
+        );
+    }
+}
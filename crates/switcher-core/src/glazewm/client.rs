@@ -0,0 +1,68 @@
+//! Fallback transport that shells out to the `glazewm` CLI, used only when
+//! the IPC server cannot be reached.
+
+use anyhow::{anyhow, Context, Result};
+use std::process::{Command, Stdio};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Runs `glazewm query monitors` and returns the raw stdout as text.
+pub fn query_monitors_text() -> Result<String> {
+    let mut cmd = Command::new("glazewm");
+    cmd.args(["query", "monitors"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(windows)]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let output = cmd
+        .output()
+        .context("failed to invoke glazewm query monitors")?;
+
+    if !output.status.success() {
+        let code = output.status.code().unwrap_or(-1);
+        tracing::warn!(target: "glazewm", "CLI failed: code={}", code);
+        return Err(anyhow!("glazewm query monitors failed"));
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout).to_string();
+    tracing::debug!(target: "glazewm", "CLI succeeded: len={}", text.len());
+    Ok(text)
+}
+
+/// Attempt to focus/change the workspace using GlazeWM CLI.
+pub fn focus_workspace(workspace_name: &str) -> Result<()> {
+    run_command(&["focus", "--workspace", workspace_name])
+}
+
+/// Focuses the monitor at `monitor_idx` in GlazeWM's monitor order.
+pub fn focus_monitor(monitor_idx: usize) -> Result<()> {
+    run_command(&["focus", "--monitor", &monitor_idx.to_string()])
+}
+
+/// Runs `glazewm command <args>`.
+pub fn run_command(args: &[&str]) -> Result<()> {
+    // GlazeWM v3 CLI expects invoking commands via `command <...>`
+    // e.g. `glazewm command focus --workspace <name>`
+    let mut cmd = Command::new("glazewm");
+    cmd.arg("command").args(args);
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(windows)]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let command = args.join(" ");
+    let status = cmd
+        .status()
+        .with_context(|| format!("failed to invoke glazewm command {command}"))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("glazewm command {command} failed"))
+    }
+}
@@ -0,0 +1,746 @@
+//! Typed model of the GlazeWM v3 IPC payloads the switcher consumes.
+//!
+//! Only the fields the switcher reads are declared, unknown fields are ignored
+//! so newer GlazeWM releases keep working as long as the major version matches.
+
+use anyhow::anyhow;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+/// Major version of GlazeWM whose IPC payloads this module understands.
+pub const SUPPORTED_MAJOR_VERSION: u64 = 3;
+
+/// Deserializes an IPC payload, naming the path of the offending field on failure.
+pub fn parse<T: DeserializeOwned>(data: serde_json::Value) -> anyhow::Result<T> {
+    serde_path_to_error::deserialize(data)
+        .map_err(|e| anyhow!("Invalid GlazeWM payload at `{}`: {}", e.path(), e.inner()))
+}
+
+/// Data of `query app-metadata`.
+#[derive(Debug, Deserialize)]
+pub struct AppMetadataDto {
+    pub version: String,
+}
+
+impl AppMetadataDto {
+    pub fn check_version(&self) -> anyhow::Result<()> {
+        let major = self
+            .version
+            .trim_start_matches('v')
+            .split('.')
+            .next()
+            .and_then(|major| major.parse::<u64>().ok());
+
+        match major {
+            Some(SUPPORTED_MAJOR_VERSION) => Ok(()),
+            _ => anyhow::bail!(
+                "GlazeWM {} is not supported, expected v{SUPPORTED_MAJOR_VERSION}.x",
+                self.version
+            ),
+        }
+    }
+}
+
+/// Data of `query monitors`.
+#[derive(Debug, Deserialize)]
+pub struct MonitorsDto {
+    pub monitors: Vec<MonitorDto>,
+}
+
+/// Data of `query binding-modes`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BindingModesDto {
+    pub binding_modes: Vec<BindingModeDto>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BindingModeDto {
+    pub name: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+impl BindingModeDto {
+    pub fn to_state(&self) -> crate::state::BindingMode {
+        crate::state::BindingMode {
+            name: self.name.clone(),
+            display_name: self.display_name.clone(),
+        }
+    }
+}
+
+/// Data of `query paused`.
+#[derive(Debug, Deserialize)]
+pub struct PausedDto {
+    pub paused: bool,
+}
+
+/// A `WmEvent` delivered to a subscription.
+#[derive(Debug, Deserialize)]
+#[serde(
+    tag = "eventType",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum WmEventDto {
+    FocusChanged {
+        focused_container: ContainerDto,
+    },
+    WorkspaceActivated {
+        activated_workspace: WorkspaceDto,
+    },
+    WorkspaceDeactivated {
+        deactivated_id: String,
+    },
+    WorkspaceUpdated {
+        updated_workspace: WorkspaceDto,
+    },
+    WindowManaged {
+        managed_window: WindowDto,
+    },
+    WindowUnmanaged {
+        unmanaged_id: String,
+    },
+    BindingModesChanged {
+        new_binding_modes: Vec<BindingModeDto>,
+    },
+    PauseChanged {
+        is_paused: bool,
+    },
+    TilingDirectionChanged {
+        direction_container: ContainerDto,
+        new_tiling_direction: TilingDirectionDto,
+    },
+    MonitorAdded,
+    MonitorRemoved,
+    /// Events the switcher doesn't care about.
+    #[serde(other)]
+    Other,
+}
+
+/// Any node of GlazeWM's container tree.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContainerDto {
+    Monitor(MonitorDto),
+    Workspace(WorkspaceDto),
+    Split(SplitDto),
+    Window(WindowDto),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorDto {
+    pub id: String,
+    pub children: Vec<WorkspaceDto>,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub dpi: u32,
+    /// GDI device name, e.g. `\\.\DISPLAY1`.
+    pub device_name: String,
+    pub hardware_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceDto {
+    pub id: String,
+    pub name: String,
+    pub parent_id: String,
+    pub has_focus: bool,
+    /// Whether this is the workspace shown on its monitor.
+    pub is_displayed: bool,
+    #[serde(default)]
+    pub tiling_direction: Option<TilingDirectionDto>,
+    pub children: Vec<ContainerDto>,
+    /// Ids of the children, the most recently focused first.
+    #[serde(default)]
+    pub child_focus_order: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TilingDirectionDto {
+    Horizontal,
+    Vertical,
+}
+
+impl TilingDirectionDto {
+    fn to_state(self) -> crate::state::TilingDirection {
+        match self {
+            TilingDirectionDto::Horizontal => crate::state::TilingDirection::Horizontal,
+            TilingDirectionDto::Vertical => crate::state::TilingDirection::Vertical,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitDto {
+    pub id: String,
+    pub children: Vec<ContainerDto>,
+    /// Ids of the children, the most recently focused first.
+    #[serde(default)]
+    pub child_focus_order: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowDto {
+    pub id: String,
+    pub parent_id: String,
+    #[serde(default)]
+    pub handle: isize,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub process_name: String,
+    #[serde(default)]
+    pub class_name: String,
+    #[serde(default)]
+    pub state: WindowStateDto,
+}
+
+/// How a window is laid out. Floating and fullscreen carry extra flags the
+/// switcher doesn't read.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WindowStateDto {
+    #[default]
+    Tiling,
+    Floating,
+    Minimized,
+    Fullscreen,
+}
+
+impl WindowDto {
+    fn to_state(&self, focused: bool) -> crate::state::Window {
+        use crate::state::WindowState;
+
+        crate::state::Window {
+            handle: self.handle,
+            title: self.title.clone(),
+            process_name: self.process_name.clone(),
+            class_name: self.class_name.clone(),
+            state: match self.state {
+                WindowStateDto::Tiling => WindowState::Tiling,
+                WindowStateDto::Floating => WindowState::Floating,
+                WindowStateDto::Minimized => WindowState::Minimized,
+                WindowStateDto::Fullscreen => WindowState::Fullscreen,
+            },
+            focused,
+        }
+    }
+}
+
+impl ContainerDto {
+    pub fn id(&self) -> &str {
+        match self {
+            ContainerDto::Monitor(m) => &m.id,
+            ContainerDto::Workspace(w) => &w.id,
+            ContainerDto::Split(s) => &s.id,
+            ContainerDto::Window(w) => &w.id,
+        }
+    }
+
+    /// Child containers, workspaces of a monitor are not walked.
+    fn children_mut(&mut self) -> Option<&mut Vec<ContainerDto>> {
+        match self {
+            ContainerDto::Workspace(w) => Some(&mut w.children),
+            ContainerDto::Split(s) => Some(&mut s.children),
+            ContainerDto::Monitor(_) | ContainerDto::Window(_) => None,
+        }
+    }
+
+    /// Collects the windows in this container, including those nested in splits.
+    fn collect_windows<'a>(&'a self, windows: &mut Vec<&'a WindowDto>) {
+        match self {
+            ContainerDto::Window(w) => windows.push(w),
+            ContainerDto::Split(s) => s.children.iter().for_each(|c| c.collect_windows(windows)),
+            ContainerDto::Workspace(w) => windows.extend(w.windows()),
+            ContainerDto::Monitor(_) => {}
+        }
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.id() == id
+            || match self {
+                ContainerDto::Workspace(w) => w.contains(id),
+                ContainerDto::Split(s) => s.children.iter().any(|c| c.contains(id)),
+                ContainerDto::Monitor(_) | ContainerDto::Window(_) => false,
+            }
+    }
+}
+
+/// Removes the container with `id` from `children` or any of their descendants.
+fn remove_container(children: &mut Vec<ContainerDto>, id: &str) -> bool {
+    let len = children.len();
+    children.retain(|c| c.id() != id);
+    if children.len() != len {
+        return true;
+    }
+
+    children
+        .iter_mut()
+        .filter_map(ContainerDto::children_mut)
+        .any(|children| remove_container(children, id))
+}
+
+/// Puts the child of `children` holding the container with `id` first in
+/// `focus_order`, and so on down to the container itself.
+fn bring_to_front(children: &mut [ContainerDto], focus_order: &mut Vec<String>, id: &str) -> bool {
+    let Some(child) = children.iter_mut().find(|c| c.contains(id)) else {
+        return false;
+    };
+
+    let child_id = child.id().to_string();
+    focus_order.retain(|c| *c != child_id);
+    focus_order.insert(0, child_id);
+
+    if let ContainerDto::Split(split) = child {
+        bring_to_front(&mut split.children, &mut split.child_focus_order, id);
+    }
+    true
+}
+
+/// Finds the children of the container with `id` among `children` or their descendants.
+fn find_children<'a>(
+    children: &'a mut [ContainerDto],
+    id: &str,
+) -> Option<&'a mut Vec<ContainerDto>> {
+    for child in children {
+        let is_match = child.id() == id;
+        let Some(grandchildren) = child.children_mut() else {
+            continue;
+        };
+        if is_match {
+            return Some(grandchildren);
+        }
+        if let Some(found) = find_children(grandchildren, id) {
+            return Some(found);
+        }
+    }
+
+    None
+}
+
+impl WorkspaceDto {
+    /// Windows on this workspace, including those nested in splits, in tree order.
+    pub fn windows(&self) -> Vec<&WindowDto> {
+        let mut windows = Vec::new();
+        for child in &self.children {
+            child.collect_windows(&mut windows);
+        }
+        windows
+    }
+
+    /// The window focused last on this workspace, following the focus order
+    /// down through splits.
+    pub fn last_focused_window(&self) -> Option<&WindowDto> {
+        let (mut children, mut focus_order) = (&self.children, &self.child_focus_order);
+        loop {
+            let id = focus_order.first()?;
+            match children.iter().find(|c| c.id() == id)? {
+                ContainerDto::Window(window) => return Some(window),
+                ContainerDto::Split(split) => {
+                    (children, focus_order) = (&split.children, &split.child_focus_order);
+                }
+                ContainerDto::Monitor(_) | ContainerDto::Workspace(_) => return None,
+            }
+        }
+    }
+
+    /// Makes the container with `id` the most recently focused one, returns
+    /// `false` if it isn't part of this workspace.
+    pub fn focus(&mut self, id: &str) -> bool {
+        bring_to_front(&mut self.children, &mut self.child_focus_order, id)
+    }
+
+    /// Whether the container with `id` lives anywhere in this workspace.
+    pub fn contains(&self, id: &str) -> bool {
+        self.children.iter().any(|c| c.contains(id))
+    }
+
+    /// Adds `window` under its parent container, returns `false` if the
+    /// parent isn't part of this workspace.
+    pub fn insert_window(&mut self, window: WindowDto) -> bool {
+        let parent = if window.parent_id == self.id {
+            Some(&mut self.children)
+        } else {
+            find_children(&mut self.children, &window.parent_id)
+        };
+
+        match parent {
+            Some(children) => {
+                children.push(ContainerDto::Window(window));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the container with `id` from this workspace.
+    pub fn remove(&mut self, id: &str) -> bool {
+        remove_container(&mut self.children, id)
+    }
+}
+
+/// Maps GlazeWM monitors into the switcher state, keeping GlazeWM's monitor order.
+pub fn to_state(monitors: &[MonitorDto]) -> crate::state::State {
+    let monitors = monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| {
+            // identical monitor models share a hardware id
+            let shared = monitors
+                .iter()
+                .filter(|m| m.hardware_id == monitor.hardware_id)
+                .count()
+                > 1;
+            monitor.to_state(index, shared)
+        })
+        .collect();
+
+    crate::state::State {
+        monitors,
+        ..Default::default()
+    }
+}
+
+impl MonitorDto {
+    pub fn has_workspace(&self, name: &str) -> bool {
+        self.children.iter().any(|w| w.name == name)
+    }
+
+    /// The `--direction` leading from this monitor towards `other`, judged by
+    /// the offset between their centers.
+    pub fn direction_to(&self, other: &MonitorDto) -> &'static str {
+        let dx = (other.x + other.width / 2) - (self.x + self.width / 2);
+        let dy = (other.y + other.height / 2) - (self.y + self.height / 2);
+
+        match (dx.abs() >= dy.abs(), dx, dy) {
+            (true, dx, _) if dx < 0 => "left",
+            (true, _, _) => "right",
+            (false, _, dy) if dy < 0 => "up",
+            (false, _, _) => "down",
+        }
+    }
+
+    /// Device name without the `\\.\` prefix, e.g. `DISPLAY1`.
+    fn short_device_name(&self) -> &str {
+        self.device_name.trim_start_matches(['\\', '.'])
+    }
+
+    /// An identity that survives GlazeWM restarts, unlike the container id.
+    fn stable_id(&self, shared_hardware_id: bool) -> String {
+        match &self.hardware_id {
+            Some(hardware_id) if shared_hardware_id => {
+                format!("{hardware_id}-{}", self.short_device_name())
+            }
+            Some(hardware_id) => hardware_id.clone(),
+            None => self.short_device_name().to_string(),
+        }
+    }
+
+    fn to_state(&self, index: usize, shared_hardware_id: bool) -> crate::state::Monitor {
+        fn parse_num(s: &str) -> Option<i32> {
+            s.trim().parse::<i32>().ok()
+        }
+
+        let mut children = self.children.iter().collect::<Vec<_>>();
+
+        // Sort by numeric name, then lexicographic.
+        children.sort_by(|a, b| match (parse_num(&a.name), parse_num(&b.name)) {
+            (Some(na), Some(nb)) => na.cmp(&nb),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => a.name.cmp(&b.name),
+        });
+
+        let workspaces = children
+            .into_iter()
+            .map(|w| {
+                let windows = w.windows();
+                let focused = w.last_focused_window().map(|f| f.id.as_str());
+                crate::state::Workspace {
+                    name: w.name.clone(),
+                    focused: w.is_displayed,
+                    is_empty: windows.is_empty(),
+                    window_count: windows.len(),
+                    windows: windows
+                        .into_iter()
+                        .map(|window| window.to_state(focused == Some(window.id.as_str())))
+                        .collect(),
+                    tiling_direction: w.tiling_direction.map(TilingDirectionDto::to_state),
+                }
+            })
+            .collect();
+
+        crate::state::Monitor {
+            name: crate::state::display_name(&self.device_name),
+            index,
+            id: self.stable_id(shared_hardware_id),
+            workspaces,
+            rect: crate::state::Rect {
+                left: self.x,
+                top: self.y,
+                right: self.x + self.width,
+                bottom: self.y + self.height,
+            },
+            dpi: self.dpi,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn monitor(
+        id: &str,
+        device: &str,
+        hardware_id: Option<&str>,
+        x: i32,
+        workspaces: Value,
+    ) -> Value {
+        json!({
+            "type": "monitor",
+            "id": id,
+            "children": workspaces,
+            "x": x,
+            "y": 0,
+            "width": 1920,
+            "height": 1080,
+            "dpi": 96,
+            "deviceName": device,
+            "hardwareId": hardware_id,
+        })
+    }
+
+    fn workspace(name: Value, displayed: bool, children: Value) -> Value {
+        json!({
+            "type": "workspace",
+            "id": format!("w{name}"),
+            "name": name,
+            "parentId": "m",
+            "hasFocus": displayed,
+            "isDisplayed": displayed,
+            "children": children,
+        })
+    }
+
+    #[test]
+    fn parse_error_names_the_failing_field() {
+        let data = json!({
+            "monitors": [monitor("m", r"\\.\DISPLAY1", None, 0, json!([workspace(json!(1), true, json!([]))]))],
+        });
+
+        let error = parse::<MonitorsDto>(data).unwrap_err().to_string();
+        assert!(error.contains("monitors[0].children[0].name"), "{error}");
+    }
+
+    #[test]
+    fn picks_direction_towards_monitor() {
+        let at = |x: i32, y: i32| {
+            let mut value = monitor("m", r"\\.\DISPLAY1", None, x, json!([]));
+            value["y"] = json!(y);
+            parse::<MonitorDto>(value).unwrap()
+        };
+
+        let origin = at(0, 0);
+        assert_eq!(origin.direction_to(&at(1920, 200)), "right");
+        assert_eq!(origin.direction_to(&at(-1920, 0)), "left");
+        assert_eq!(origin.direction_to(&at(300, -1080)), "up");
+        assert_eq!(origin.direction_to(&at(0, 1080)), "down");
+    }
+
+    #[test]
+    fn rejects_other_major_versions() {
+        let metadata = |version: &str| AppMetadataDto {
+            version: version.into(),
+        };
+
+        assert!(metadata("3.9.1").check_version().is_ok());
+        assert!(metadata("v3.0.0").check_version().is_ok());
+        assert!(metadata("2.1.0").check_version().is_err());
+        assert!(metadata("unknown").check_version().is_err());
+    }
+
+    #[test]
+    fn maps_monitors_into_state() {
+        let split = json!([
+            {
+                "type": "window",
+                "id": "f",
+                "parentId": "w2",
+                "handle": 1312,
+                "title": "Mozilla Firefox",
+                "processName": "firefox",
+                "className": "MozillaWindowClass",
+                "state": { "type": "floating", "centered": true, "shownOnTop": false },
+                "hasFocus": false,
+            },
+            {
+                "type": "split",
+                "id": "s",
+                "children": [
+                    { "type": "window", "id": "x", "parentId": "s" },
+                    {
+                        "type": "split",
+                        "id": "t",
+                        "children": [{ "type": "window", "id": "y", "parentId": "t" }],
+                    },
+                ],
+            },
+        ]);
+        let data = json!({
+            "monitors": [monitor(
+                "m",
+                r"\\.\DISPLAY1",
+                Some("DEL40F6"),
+                0,
+                json!([workspace(json!("2"), false, split), workspace(json!("1"), true, json!([]))]),
+            )],
+        });
+
+        let monitors = parse::<MonitorsDto>(data).unwrap().monitors;
+        let state = to_state(&monitors);
+
+        let workspaces = &state.monitors[0].workspaces;
+        assert_eq!(workspaces[0].name, "1");
+        assert!(workspaces[0].focused && workspaces[0].is_empty);
+        assert_eq!(workspaces[0].window_count, 0);
+        assert_eq!(workspaces[1].name, "2");
+        assert!(!workspaces[1].focused && !workspaces[1].is_empty);
+        assert_eq!(workspaces[1].window_count, 3);
+
+        let window = &workspaces[1].windows[0];
+        assert_eq!(window.handle, 1312);
+        assert_eq!(window.title, "Mozilla Firefox");
+        assert_eq!(window.process_name, "firefox");
+        assert_eq!(window.class_name, "MozillaWindowClass");
+        assert_eq!(window.state, crate::state::WindowState::Floating);
+    }
+
+    #[test]
+    fn follows_focus_order_to_last_focused_window() {
+        let mut value = workspace(
+            json!("1"),
+            true,
+            json!([
+                { "type": "window", "id": "f", "parentId": "w1" },
+                {
+                    "type": "split",
+                    "id": "s",
+                    "childFocusOrder": ["t", "x"],
+                    "children": [
+                        { "type": "window", "id": "x", "parentId": "s" },
+                        {
+                            "type": "split",
+                            "id": "t",
+                            "childFocusOrder": ["y"],
+                            "children": [{ "type": "window", "id": "y", "parentId": "t" }],
+                        },
+                    ],
+                },
+            ]),
+        );
+        value["childFocusOrder"] = json!(["s", "f"]);
+        let mut workspace = parse::<WorkspaceDto>(value.clone()).unwrap();
+
+        let last_focused = |w: &WorkspaceDto| w.last_focused_window().map(|w| w.id.clone());
+        assert_eq!(last_focused(&workspace).as_deref(), Some("y"));
+
+        assert!(workspace.focus("x"));
+        assert_eq!(last_focused(&workspace).as_deref(), Some("x"));
+        assert!(workspace.focus("f"));
+        assert_eq!(last_focused(&workspace).as_deref(), Some("f"));
+        assert!(!workspace.focus("z"));
+
+        let monitor = parse::<MonitorDto>(monitor("m", r"\\.\DISPLAY1", None, 0, json!([value])));
+        let state = to_state(&[monitor.unwrap()]);
+        let focused = state.monitors[0].workspaces[0]
+            .windows
+            .iter()
+            .filter(|w| w.focused)
+            .count();
+        assert_eq!(focused, 1);
+    }
+
+    #[test]
+    fn keeps_real_workspace_names() {
+        let data = json!({
+            "monitors": [monitor(
+                "m",
+                r"\\.\DISPLAY1",
+                None,
+                0,
+                json!([
+                    workspace(json!("web"), false, json!([])),
+                    workspace(json!("10"), false, json!([])),
+                    workspace(json!("3"), true, json!([])),
+                ]),
+            )],
+        });
+
+        let monitors = parse::<MonitorsDto>(data).unwrap().monitors;
+        let state = to_state(&monitors);
+
+        let workspaces = state.monitors[0]
+            .workspaces
+            .iter()
+            .map(|w| w.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(workspaces, ["3", "10", "web"]);
+    }
+
+    #[test]
+    fn monitors_get_geometry_and_stable_identity() {
+        let data = json!({
+            "monitors": [
+                monitor("a", r"\\.\DISPLAY2", Some("DEL40F6"), 1920, json!([])),
+                monitor("b", r"\\.\DISPLAY1", Some("DEL40F6"), 0, json!([])),
+                monitor("c", r"\\.\DISPLAY3", Some("GSM5B7F"), -1920, json!([])),
+                monitor("d", r"\\.\DISPLAY4", None, 3840, json!([])),
+            ],
+        });
+
+        let monitors = parse::<MonitorsDto>(data).unwrap().monitors;
+        let state = to_state(&monitors);
+
+        let ids = state
+            .monitors
+            .iter()
+            .map(|m| m.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            [
+                "DEL40F6-DISPLAY2",
+                "DEL40F6-DISPLAY1",
+                "GSM5B7F",
+                "DISPLAY4"
+            ]
+        );
+
+        let monitor = &state.monitors[2];
+        assert_eq!(monitor.name, "Display 3");
+        assert_eq!(monitor.index, 2);
+        assert_eq!(monitor.dpi, 96);
+        assert_eq!(
+            (
+                monitor.rect.left,
+                monitor.rect.top,
+                monitor.rect.right,
+                monitor.rect.bottom
+            ),
+            (-1920, 0, 0, 1080)
+        );
+    }
+}
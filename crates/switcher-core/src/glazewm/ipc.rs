@@ -0,0 +1,345 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::io::ErrorKind;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+use tungstenite::{Message, WebSocket};
+
+/// Address of GlazeWM's IPC server unless configured otherwise.
+pub const DEFAULT_URL: &str = "ws://localhost:6123";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A message pushed by the GlazeWM IPC server.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "messageType", rename_all = "snake_case")]
+pub enum ServerMessage {
+    ClientResponse(ClientResponse),
+    EventSubscription(EventSubscription),
+    /// Anything newer than this client understands.
+    #[serde(other)]
+    Other,
+}
+
+/// Reply to a message sent by this client, echoing the original message.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientResponse {
+    pub client_message: String,
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub success: bool,
+}
+
+impl ClientResponse {
+    /// Turns an unsuccessful response into an error carrying GlazeWM's message.
+    pub fn into_data(self) -> anyhow::Result<serde_json::Value> {
+        if !self.success {
+            let error = self.error.unwrap_or_else(|| "unknown error".into());
+            anyhow::bail!("GlazeWM rejected `{}`: {error}", self.client_message);
+        }
+
+        Ok(self.data.unwrap_or_default())
+    }
+}
+
+/// An event delivered for a subscription made with [`IpcClient::subscribe`].
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSubscription {
+    pub subscription_id: String,
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub success: bool,
+}
+
+/// The connection to GlazeWM's IPC server failed, as opposed to GlazeWM
+/// rejecting a message. The connection can't be used after this.
+#[derive(Debug)]
+pub struct TransportError(pub anyhow::Error);
+
+impl TransportError {
+    /// Whether `error` was caused by a failed connection.
+    pub fn is_cause_of(error: &anyhow::Error) -> bool {
+        error.downcast_ref::<Self>().is_some()
+    }
+}
+
+impl std::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#}", self.0)
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+/// Quotes an argument for a client message. GlazeWM splits messages like a
+/// shell command line, so names with spaces or quotes must be quoted.
+pub fn quote_arg(arg: &str) -> Cow<'_, str> {
+    let needs_quotes = arg.is_empty()
+        || arg
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\'));
+    if !needs_quotes {
+        return Cow::Borrowed(arg);
+    }
+
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        if matches!(c, '"' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    Cow::Owned(quoted)
+}
+
+/// A blocking connection to GlazeWM's WebSocket IPC server.
+pub struct IpcClient {
+    socket: WebSocket<TcpStream>,
+    /// Events that arrived while waiting for a response.
+    pending_events: VecDeque<EventSubscription>,
+}
+
+impl IpcClient {
+    pub fn connect(url: &str) -> anyhow::Result<Self> {
+        let uri: tungstenite::http::Uri = url
+            .parse()
+            .with_context(|| format!("Invalid GlazeWM IPC url: {url}"))?;
+        let host = uri.host().context("GlazeWM IPC url has no host")?;
+        let port = uri.port_u16().unwrap_or(80);
+
+        let stream = (host, port)
+            .to_socket_addrs()?
+            .find_map(|addr| TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).ok())
+            .with_context(|| format!("Failed to connect to GlazeWM IPC server at {url}"))?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+        let (socket, _) = tungstenite::client(url, stream)
+            .map_err(|e| anyhow!("WebSocket handshake with {url} failed: {e}"))?;
+
+        tracing::debug!(target: "glazewm", "Connected to IPC server at {url}");
+
+        Ok(Self {
+            socket,
+            pending_events: VecDeque::new(),
+        })
+    }
+
+    /// Runs `glazewm query <query>` and returns the response data.
+    pub fn query(&mut self, query: &str) -> anyhow::Result<serde_json::Value> {
+        self.send_message(&format!("query {query}"))?.into_data()
+    }
+
+    /// Runs `glazewm command <command>`.
+    pub fn command(&mut self, command: &str) -> anyhow::Result<()> {
+        self.send_message(&format!("command {command}"))?
+            .into_data()
+            .map(|_| ())
+    }
+
+    /// Subscribes this connection to the given `WmEvent`s and returns the
+    /// subscription id. Events are then read with [`IpcClient::next_event`].
+    pub fn subscribe(&mut self, events: &[&str]) -> anyhow::Result<String> {
+        let data = self
+            .send_message(&format!("sub --events {}", events.join(" ")))?
+            .into_data()?;
+
+        data.get("subscriptionId")
+            .and_then(|id| id.as_str())
+            .map(ToString::to_string)
+            .context("GlazeWM did not return a subscription id")
+    }
+
+    /// Waits for the next subscription event and returns its payload.
+    /// Returns `None` when nothing arrived within the read timeout.
+    pub fn next_event(&mut self) -> anyhow::Result<Option<serde_json::Value>> {
+        let event = match self.pending_events.pop_front() {
+            Some(event) => event,
+            None => loop {
+                match self.read_message()? {
+                    Some(ServerMessage::EventSubscription(event)) => break event,
+                    Some(other) => {
+                        tracing::trace!(target: "glazewm", "Skipping unrelated IPC message: {other:?}")
+                    }
+                    None => return Ok(None),
+                }
+            },
+        };
+
+        if !event.success {
+            let error = event.error.unwrap_or_else(|| "unknown error".into());
+            anyhow::bail!("Subscription {} failed: {error}", event.subscription_id);
+        }
+
+        Ok(event.data)
+    }
+
+    /// Sends a raw client message and waits for the response that echoes it.
+    /// Only failures of the connection itself are errors, a rejected message
+    /// is returned as an unsuccessful response.
+    pub fn send_message(&mut self, message: &str) -> Result<ClientResponse, TransportError> {
+        self.socket
+            .send(Message::text(message))
+            .map_err(|e| TransportError(e.into()))?;
+
+        loop {
+            match self.read_message().map_err(TransportError)? {
+                Some(ServerMessage::ClientResponse(response))
+                    if response.client_message == message =>
+                {
+                    return Ok(response)
+                }
+                Some(ServerMessage::EventSubscription(event)) => {
+                    self.pending_events.push_back(event)
+                }
+                Some(other) => {
+                    tracing::trace!(target: "glazewm", "Skipping unrelated IPC message: {other:?}")
+                }
+                None => {
+                    return Err(TransportError(anyhow!(
+                        "Timed out waiting for GlazeWM to answer `{message}`"
+                    )))
+                }
+            }
+        }
+    }
+
+    /// Reads the next server message, or `None` if the read timed out.
+    fn read_message(&mut self) -> anyhow::Result<Option<ServerMessage>> {
+        loop {
+            let text = match self.socket.read() {
+                Ok(Message::Text(text)) => text,
+                Ok(Message::Close(_)) => {
+                    anyhow::bail!("GlazeWM IPC server closed the connection")
+                }
+                // pings are answered by tungstenite on the next read
+                Ok(_) => continue,
+                Err(tungstenite::Error::Io(e))
+                    if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    return Ok(None)
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            return serde_json::from_str(&text)
+                .map(Some)
+                .with_context(|| format!("Malformed IPC message from GlazeWM: {text}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::command_line::split_args;
+    use crate::glazewm::stand_in::{self, event, response};
+
+    #[test]
+    fn query_returns_response_data() {
+        let url = stand_in::spawn(|message| {
+            assert_eq!(message, "query workspaces");
+            vec![response(
+                message,
+                json!({ "workspaces": [{ "name": "1" }] }),
+            )]
+        });
+
+        let mut client = IpcClient::connect(&url).unwrap();
+        let data = client.query("workspaces").unwrap();
+        assert_eq!(data["workspaces"][0]["name"], "1");
+    }
+
+    #[test]
+    fn buffers_events_and_skips_unrelated_responses() {
+        let url = stand_in::spawn(|message| {
+            vec![
+                event(json!({ "eventType": "focus_changed" })),
+                response("query monitors", json!({ "monitors": [] })),
+                response(message, json!({ "paused": true })),
+            ]
+        });
+
+        let mut client = IpcClient::connect(&url).unwrap();
+        assert_eq!(client.query("paused").unwrap()["paused"], true);
+
+        let event = client.next_event().unwrap().unwrap();
+        assert_eq!(event["eventType"], "focus_changed");
+    }
+
+    #[test]
+    fn subscribe_returns_subscription_id() {
+        let url = stand_in::spawn(|message| {
+            assert_eq!(message, "sub --events focus_changed pause_changed");
+            vec![response(message, json!({ "subscriptionId": "sub-1" }))]
+        });
+
+        let mut client = IpcClient::connect(&url).unwrap();
+        let id = client
+            .subscribe(&["focus_changed", "pause_changed"])
+            .unwrap();
+        assert_eq!(id, "sub-1");
+    }
+
+    #[test]
+    fn command_failure_reports_glazewm_error() {
+        let url = stand_in::spawn(|message| {
+            vec![json!({
+                "messageType": "client_response",
+                "clientMessage": message,
+                "data": null,
+                "error": "Workspace not found.",
+                "success": false,
+            })]
+        });
+
+        let mut client = IpcClient::connect(&url).unwrap();
+        let error = client.command("focus --workspace 9").unwrap_err();
+        assert!(error.to_string().contains("Workspace not found."));
+        assert!(!TransportError::is_cause_of(&error));
+    }
+
+    #[test]
+    fn quote_arg_only_quotes_when_needed() {
+        assert_eq!(quote_arg("10b"), "10b");
+        assert_eq!(quote_arg("web dev"), "\"web dev\"");
+        assert_eq!(quote_arg(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(quote_arg(""), "\"\"");
+    }
+
+    #[test]
+    fn split_args_undoes_quote_arg() {
+        for name in ["10b", "web dev", r#"say "hi""#, r"C:\dev", "it's", ""] {
+            let message = format!("wm-disable-binding-mode --name {}", quote_arg(name));
+            assert_eq!(
+                split_args(&message).unwrap(),
+                ["wm-disable-binding-mode", "--name", name]
+            );
+        }
+    }
+
+    #[test]
+    fn connect_fails_without_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        assert!(IpcClient::connect(&url).is_err());
+    }
+}
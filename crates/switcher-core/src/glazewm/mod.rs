@@ -0,0 +1,647 @@
+use std::sync::{Mutex, PoisonError};
+
+use anyhow::Context;
+
+use crate::provider::{MenuCommand, WorkspaceAction, WorkspaceProvider};
+use crate::state::State;
+
+mod client;
+mod dto;
+mod ipc;
+#[cfg(test)]
+mod stand_in;
+mod subscription;
+
+pub use ipc::DEFAULT_URL;
+
+/// Commands offered in the context menu.
+const MENU_COMMANDS: &[MenuCommand] = &[
+    MenuCommand {
+        label: "Reload config",
+        command: "wm-reload-config",
+    },
+    MenuCommand {
+        label: "Redraw",
+        command: "wm-redraw",
+    },
+    MenuCommand {
+        label: "Toggle floating",
+        command: "toggle-floating",
+    },
+    MenuCommand {
+        label: "Toggle fullscreen",
+        command: "toggle-fullscreen",
+    },
+    MenuCommand {
+        label: "Minimize",
+        command: "toggle-minimized",
+    },
+    MenuCommand {
+        label: "Exit GlazeWM",
+        command: "wm-exit",
+    },
+];
+
+/// Actions offered in the context menu of a workspace.
+const WORKSPACE_ACTIONS: &[WorkspaceAction] = &[
+    WorkspaceAction::Focus,
+    WorkspaceAction::MoveFocusedWindowHere,
+    WorkspaceAction::MoveFocusedWindowAndFollow,
+    WorkspaceAction::MoveToNextMonitor,
+    WorkspaceAction::MoveToPreviousMonitor,
+    WorkspaceAction::CloseAllWindows,
+];
+
+/// Connects to GlazeWM's IPC server and checks that it speaks a supported version.
+fn connect(url: &str) -> anyhow::Result<ipc::IpcClient> {
+    let mut client = ipc::IpcClient::connect(url)?;
+    let metadata: dto::AppMetadataDto = dto::parse(client.query("app-metadata")?)?;
+    metadata.check_version()?;
+    Ok(client)
+}
+
+/// Parses the output of `glazewm query monitors`, which prints the same
+/// response envelope as the IPC server. Plain workspace names are accepted
+/// too, for wrappers that mimic the CLI.
+fn parse_cli_output(text: &str) -> anyhow::Result<crate::state::State> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(text) else {
+        let state = State::from_plain_text(text, "glazewm-default")?;
+        let count = state
+            .monitors
+            .first()
+            .map(|m| m.workspaces.len())
+            .unwrap_or(0);
+        tracing::debug!(target: "glazewm", "parsed plain workspaces={}", count);
+        return Ok(state);
+    };
+
+    let response: ipc::ClientResponse = dto::parse(value)?;
+    let data: dto::MonitorsDto = dto::parse(response.into_data()?)?;
+    Ok(dto::to_state(&data.monitors))
+}
+
+/// Whether the output of `glazewm query monitors` puts the workspace named
+/// `workspace_name` on the monitor at `monitor_idx`.
+fn cli_has_workspace(text: &str, monitor_idx: usize, workspace_name: &str) -> anyhow::Result<bool> {
+    let state = parse_cli_output(text)?;
+    Ok(state
+        .monitors
+        .get(monitor_idx)
+        .is_some_and(|m| m.workspaces.iter().any(|w| w.name == workspace_name)))
+}
+
+/// Focuses a workspace after checking against GlazeWM's current state that it
+/// still belongs to the monitor at `monitor_idx`. Returns `false` if it doesn't.
+fn activate_workspace(
+    ipc: &mut ipc::IpcClient,
+    monitor_idx: usize,
+    workspace_name: &str,
+    focus_monitor: bool,
+) -> anyhow::Result<bool> {
+    let data: dto::MonitorsDto = dto::parse(ipc.query("monitors")?)?;
+    let on_monitor = data
+        .monitors
+        .get(monitor_idx)
+        .is_some_and(|m| m.has_workspace(workspace_name));
+    if !on_monitor {
+        return Ok(false);
+    }
+
+    focus_workspace(ipc, monitor_idx, workspace_name, focus_monitor)?;
+    Ok(true)
+}
+
+/// Focuses a workspace known to be on the monitor at `monitor_idx`, focusing
+/// that monitor first if `focus_monitor` is set.
+fn focus_workspace(
+    ipc: &mut ipc::IpcClient,
+    monitor_idx: usize,
+    workspace_name: &str,
+    focus_monitor: bool,
+) -> anyhow::Result<()> {
+    if focus_monitor {
+        ipc.command(&format!("focus --monitor {monitor_idx}"))?;
+    }
+    ipc.command(&format!(
+        "focus --workspace {}",
+        ipc::quote_arg(workspace_name)
+    ))
+}
+
+/// Runs `action` on a workspace after checking that it still belongs to the
+/// monitor at `monitor_idx`. Returns `false` if it doesn't.
+fn run_workspace_action(
+    ipc: &mut ipc::IpcClient,
+    monitor_idx: usize,
+    workspace_name: &str,
+    action: WorkspaceAction,
+    focus_monitor: bool,
+) -> anyhow::Result<bool> {
+    let data: dto::MonitorsDto = dto::parse(ipc.query("monitors")?)?;
+    let Some(workspace) = data
+        .monitors
+        .get(monitor_idx)
+        .and_then(|m| m.children.iter().find(|w| w.name == workspace_name))
+    else {
+        return Ok(false);
+    };
+
+    let name = ipc::quote_arg(workspace_name);
+    match action {
+        WorkspaceAction::Focus => {
+            focus_workspace(ipc, monitor_idx, workspace_name, focus_monitor)?;
+        }
+        WorkspaceAction::MoveFocusedWindowHere => {
+            ipc.command(&format!("move --workspace {name}"))?;
+        }
+        WorkspaceAction::MoveFocusedWindowAndFollow => {
+            ipc.command(&format!("move --workspace {name}"))?;
+            ipc.command(&format!("focus --workspace {name}"))?;
+        }
+        // `move-workspace` acts on the focused workspace.
+        WorkspaceAction::MoveToNextMonitor | WorkspaceAction::MoveToPreviousMonitor => {
+            let direction = match action {
+                WorkspaceAction::MoveToNextMonitor => "right",
+                _ => "left",
+            };
+            ipc.command(&format!("focus --workspace {name}"))?;
+            ipc.command(&format!("move-workspace --direction {direction}"))?;
+        }
+        WorkspaceAction::CloseAllWindows => {
+            for window in workspace.windows() {
+                ipc.command(&format!("--id {} close", window.id))?;
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+/// Moves a workspace towards the monitor at `target_monitor_idx`. GlazeWM
+/// only moves workspaces by direction, so it lands on the nearest monitor that
+/// way. Returns `false` if the workspace isn't on the monitor at `monitor_idx`.
+fn move_workspace_to_monitor(
+    ipc: &mut ipc::IpcClient,
+    monitor_idx: usize,
+    workspace_name: &str,
+    target_monitor_idx: usize,
+) -> anyhow::Result<bool> {
+    let data: dto::MonitorsDto = dto::parse(ipc.query("monitors")?)?;
+    let (Some(monitor), Some(target)) = (
+        data.monitors.get(monitor_idx),
+        data.monitors.get(target_monitor_idx),
+    ) else {
+        return Ok(false);
+    };
+    if !monitor.has_workspace(workspace_name) {
+        return Ok(false);
+    }
+
+    let direction = monitor.direction_to(target);
+    ipc.command(&format!(
+        "focus --workspace {}",
+        ipc::quote_arg(workspace_name)
+    ))?;
+    ipc.command(&format!("move-workspace --direction {direction}"))?;
+
+    Ok(true)
+}
+
+/// Toggles the tiling direction of a workspace, targeting it by id since
+/// `toggle-tiling-direction` acts on the focused container otherwise. Returns
+/// `false` if the workspace isn't on the monitor at `monitor_idx`.
+fn toggle_tiling_direction(
+    ipc: &mut ipc::IpcClient,
+    monitor_idx: usize,
+    workspace_name: &str,
+) -> anyhow::Result<bool> {
+    let data: dto::MonitorsDto = dto::parse(ipc.query("monitors")?)?;
+    let Some(workspace) = data
+        .monitors
+        .get(monitor_idx)
+        .and_then(|m| m.children.iter().find(|w| w.name == workspace_name))
+    else {
+        return Ok(false);
+    };
+
+    ipc.command(&format!("--id {} toggle-tiling-direction", workspace.id))?;
+
+    Ok(true)
+}
+
+/// The GlazeWM backend, talking to its IPC server with the CLI as a fallback.
+pub struct GlazeWm {
+    url: String,
+    /// Connection shared by queries and commands, opened lazily.
+    ipc: Mutex<Option<ipc::IpcClient>>,
+}
+
+impl GlazeWm {
+    /// Talks to the IPC server at `url`, usually [`DEFAULT_URL`].
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            ipc: Mutex::new(None),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.with_ipc(|_| Ok(())).is_ok()
+    }
+
+    /// Runs `f` on the shared IPC connection, connecting first if needed.
+    /// The connection is dropped when it fails so the next call reconnects,
+    /// failing to connect counts as a [`ipc::TransportError`] too.
+    fn with_ipc<T>(
+        &self,
+        f: impl FnOnce(&mut ipc::IpcClient) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let mut guard = self.ipc.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let client = match guard.as_mut() {
+            Some(client) => client,
+            None => guard.insert(connect(&self.url).map_err(ipc::TransportError)?),
+        };
+
+        let result = f(client);
+        if result.as_ref().is_err_and(ipc::TransportError::is_cause_of) {
+            *guard = None;
+        }
+
+        result
+    }
+
+    /// Reads GlazeWM state over IPC, falling back to the `glazewm` CLI when
+    /// the IPC server can't be reached.
+    fn read_state(&self) -> anyhow::Result<State> {
+        match self.with_ipc(subscription::Snapshot::query) {
+            Ok(snapshot) => return Ok(snapshot.to_state()),
+            Err(e) if ipc::TransportError::is_cause_of(&e) => {
+                tracing::debug!(target: "glazewm", "IPC unavailable, falling back to CLI: {e}")
+            }
+            Err(e) => return Err(e),
+        }
+
+        let text = client::query_monitors_text().context("GlazeWM is not running")?;
+        parse_cli_output(&text)
+    }
+}
+
+impl WorkspaceProvider for GlazeWm {
+    fn name(&self) -> &str {
+        "GlazeWM"
+    }
+
+    fn snapshot(&self) -> anyhow::Result<State> {
+        self.read_state()
+    }
+
+    fn watch(&self, sink: &mut dyn FnMut(State)) -> anyhow::Result<()> {
+        subscription::watch(&self.url, sink)
+    }
+
+    fn activate_workspace(
+        &self,
+        monitor_idx: usize,
+        workspace_name: &str,
+        focus_monitor: bool,
+    ) -> anyhow::Result<()> {
+        match self
+            .with_ipc(|ipc| activate_workspace(ipc, monitor_idx, workspace_name, focus_monitor))
+        {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                anyhow::bail!("Workspace {workspace_name:?} is not on monitor {monitor_idx}")
+            }
+            Err(e) if !ipc::TransportError::is_cause_of(&e) => Err(e),
+            Err(e) => {
+                tracing::debug!(target: "glazewm", "IPC focus failed, falling back to CLI: {e}");
+                let text = client::query_monitors_text().context("GlazeWM is not running")?;
+                if !cli_has_workspace(&text, monitor_idx, workspace_name)? {
+                    anyhow::bail!("Workspace {workspace_name:?} is not on monitor {monitor_idx}");
+                }
+                if focus_monitor {
+                    client::focus_monitor(monitor_idx)?;
+                }
+                client::focus_workspace(workspace_name)
+            }
+        }
+    }
+
+    fn workspace_actions(&self) -> &[WorkspaceAction] {
+        WORKSPACE_ACTIONS
+    }
+
+    fn run_workspace_action(
+        &self,
+        monitor_idx: usize,
+        workspace_name: &str,
+        action: WorkspaceAction,
+        focus_monitor: bool,
+    ) -> anyhow::Result<()> {
+        let done = self.with_ipc(|ipc| {
+            run_workspace_action(ipc, monitor_idx, workspace_name, action, focus_monitor)
+        })?;
+        if !done {
+            anyhow::bail!("Workspace {workspace_name:?} is not on monitor {monitor_idx}");
+        }
+
+        Ok(())
+    }
+
+    fn move_workspace_to_monitor(
+        &self,
+        monitor_idx: usize,
+        workspace_name: &str,
+        target_monitor_idx: usize,
+    ) -> anyhow::Result<()> {
+        let moved = self.with_ipc(|ipc| {
+            move_workspace_to_monitor(ipc, monitor_idx, workspace_name, target_monitor_idx)
+        })?;
+        if !moved {
+            anyhow::bail!("Workspace {workspace_name:?} is not on monitor {monitor_idx}");
+        }
+
+        Ok(())
+    }
+
+    fn disable_binding_mode(&self, name: &str) -> anyhow::Result<()> {
+        self.run_command(&format!(
+            "wm-disable-binding-mode --name {}",
+            ipc::quote_arg(name)
+        ))
+    }
+
+    fn supports_pause(&self) -> bool {
+        true
+    }
+
+    fn toggle_pause(&self) -> anyhow::Result<()> {
+        self.run_command("wm-toggle-pause")
+    }
+
+    fn toggle_tiling_direction(
+        &self,
+        monitor_idx: usize,
+        workspace_name: &str,
+    ) -> anyhow::Result<()> {
+        let toggled =
+            self.with_ipc(|ipc| toggle_tiling_direction(ipc, monitor_idx, workspace_name))?;
+        if !toggled {
+            anyhow::bail!("Workspace {workspace_name:?} is not on monitor {monitor_idx}");
+        }
+
+        Ok(())
+    }
+
+    fn run_command(&self, command: &str) -> anyhow::Result<()> {
+        match self.with_ipc(|ipc| ipc.command(command)) {
+            Err(e) if ipc::TransportError::is_cause_of(&e) => {
+                tracing::debug!(target: "glazewm", "IPC command failed, falling back to CLI: {e}");
+                let args = crate::command_line::split_args(command)?;
+                client::run_command(&args.iter().map(String::as_str).collect::<Vec<_>>())
+            }
+            result => result,
+        }
+    }
+
+    fn menu_commands(&self) -> &[MenuCommand] {
+        MENU_COMMANDS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::glazewm::stand_in::{self, response};
+
+    fn monitors() -> serde_json::Value {
+        let monitor = |id: &str, workspace: &str| {
+            json!({
+                "type": "monitor",
+                "id": id,
+                "children": [{
+                    "type": "workspace",
+                    "id": format!("w-{workspace}"),
+                    "name": workspace,
+                    "parentId": id,
+                    "hasFocus": false,
+                    "isDisplayed": true,
+                    "children": [],
+                }],
+                "x": 0,
+                "y": 0,
+                "width": 1920,
+                "height": 1080,
+                "dpi": 96,
+                "deviceName": "DISPLAY1",
+                "hardwareId": null,
+            })
+        };
+
+        json!({ "monitors": [monitor("a", "1"), monitor("b", "web dev")] })
+    }
+
+    #[test]
+    fn activates_workspace_on_its_monitor() {
+        let url = stand_in::scripted(vec![
+            (
+                "query monitors",
+                vec![response("query monitors", monitors())],
+            ),
+            (
+                "command focus --monitor 1",
+                vec![response("command focus --monitor 1", json!(null))],
+            ),
+            (
+                r#"command focus --workspace "web dev""#,
+                vec![response(
+                    r#"command focus --workspace "web dev""#,
+                    json!(null),
+                )],
+            ),
+        ]);
+
+        let mut ipc = ipc::IpcClient::connect(&url).unwrap();
+        assert!(activate_workspace(&mut ipc, 1, "web dev", true).unwrap());
+    }
+
+    #[test]
+    fn ignores_workspace_of_another_monitor() {
+        let query = || {
+            (
+                "query monitors",
+                vec![response("query monitors", monitors())],
+            )
+        };
+        let url = stand_in::scripted(vec![query(), query()]);
+
+        let mut ipc = ipc::IpcClient::connect(&url).unwrap();
+        assert!(!activate_workspace(&mut ipc, 0, "web dev", false).unwrap());
+        assert!(!activate_workspace(&mut ipc, 5, "1", false).unwrap());
+    }
+
+    #[test]
+    fn checks_monitor_of_workspace_in_cli_output() {
+        let text = response("query monitors", monitors()).to_string();
+        assert!(cli_has_workspace(&text, 1, "web dev").unwrap());
+        assert!(!cli_has_workspace(&text, 0, "web dev").unwrap());
+        assert!(!cli_has_workspace(&text, 5, "1").unwrap());
+    }
+
+    #[test]
+    fn closes_every_window_of_a_workspace() {
+        let mut data = monitors();
+        data["monitors"][1]["children"][0]["children"] = json!([
+            { "type": "window", "id": "x", "parentId": "w-web dev" },
+            {
+                "type": "split",
+                "id": "s",
+                "children": [{ "type": "window", "id": "y", "parentId": "s" }],
+            },
+        ]);
+        let url = stand_in::scripted(vec![
+            ("query monitors", vec![response("query monitors", data)]),
+            (
+                "command --id x close",
+                vec![response("command --id x close", json!(null))],
+            ),
+            (
+                "command --id y close",
+                vec![response("command --id y close", json!(null))],
+            ),
+        ]);
+
+        let mut ipc = ipc::IpcClient::connect(&url).unwrap();
+        let action = WorkspaceAction::CloseAllWindows;
+        assert!(run_workspace_action(&mut ipc, 1, "web dev", action, false).unwrap());
+    }
+
+    #[test]
+    fn focus_action_only_focuses_the_monitor_when_asked() {
+        let query = || {
+            (
+                "query monitors",
+                vec![response("query monitors", monitors())],
+            )
+        };
+        let url = stand_in::scripted(vec![
+            query(),
+            (
+                "command focus --workspace 1",
+                vec![response("command focus --workspace 1", json!(null))],
+            ),
+        ]);
+
+        let mut ipc = ipc::IpcClient::connect(&url).unwrap();
+        let action = WorkspaceAction::Focus;
+        assert!(run_workspace_action(&mut ipc, 0, "1", action, false).unwrap());
+    }
+
+    #[test]
+    fn toggles_tiling_direction_of_the_clicked_workspace() {
+        let url = stand_in::scripted(vec![
+            (
+                "query monitors",
+                vec![response("query monitors", monitors())],
+            ),
+            (
+                "command --id w-1 toggle-tiling-direction",
+                vec![response(
+                    "command --id w-1 toggle-tiling-direction",
+                    json!(null),
+                )],
+            ),
+            (
+                "query monitors",
+                vec![response("query monitors", monitors())],
+            ),
+        ]);
+
+        let mut ipc = ipc::IpcClient::connect(&url).unwrap();
+        assert!(toggle_tiling_direction(&mut ipc, 0, "1").unwrap());
+        assert!(!toggle_tiling_direction(&mut ipc, 1, "1").unwrap());
+    }
+
+    #[test]
+    fn moves_workspace_towards_target_monitor() {
+        let mut data = monitors();
+        data["monitors"][1]["x"] = json!(-1920);
+        let url = stand_in::scripted(vec![
+            ("query monitors", vec![response("query monitors", data)]),
+            (
+                "command focus --workspace 1",
+                vec![response("command focus --workspace 1", json!(null))],
+            ),
+            (
+                "command move-workspace --direction left",
+                vec![response(
+                    "command move-workspace --direction left",
+                    json!(null),
+                )],
+            ),
+        ]);
+
+        let mut ipc = ipc::IpcClient::connect(&url).unwrap();
+        assert!(move_workspace_to_monitor(&mut ipc, 0, "1", 1).unwrap());
+    }
+
+    #[test]
+    fn moves_workspace_to_the_next_monitor() {
+        let url = stand_in::scripted(vec![
+            (
+                "query monitors",
+                vec![response("query monitors", monitors())],
+            ),
+            (
+                "command focus --workspace 1",
+                vec![response("command focus --workspace 1", json!(null))],
+            ),
+            (
+                "command move-workspace --direction right",
+                vec![response(
+                    "command move-workspace --direction right",
+                    json!(null),
+                )],
+            ),
+        ]);
+
+        let mut ipc = ipc::IpcClient::connect(&url).unwrap();
+        let action = WorkspaceAction::MoveToNextMonitor;
+        assert!(run_workspace_action(&mut ipc, 0, "1", action, false).unwrap());
+    }
+
+    #[test]
+    fn rejected_command_keeps_the_connection_and_skips_the_cli() {
+        let rejected = json!({
+            "messageType": "client_response",
+            "clientMessage": "command wm-toggle-pause",
+            "data": null,
+            "error": "Not allowed.",
+            "success": false,
+        });
+        let url = stand_in::scripted(vec![
+            (
+                "query app-metadata",
+                vec![response(
+                    "query app-metadata",
+                    json!({ "version": "3.9.0" }),
+                )],
+            ),
+            ("command wm-toggle-pause", vec![rejected]),
+            (
+                "command wm-redraw",
+                vec![response("command wm-redraw", json!(null))],
+            ),
+        ]);
+
+        let glazewm = GlazeWm::new(url);
+        let error = glazewm.run_command("wm-toggle-pause").unwrap_err();
+        assert!(error.to_string().contains("Not allowed."), "{error:#}");
+        // the stand-in only accepts one connection, so this fails if the
+        // rejection dropped it
+        glazewm.run_command("wm-redraw").unwrap();
+    }
+}
@@ -0,0 +1,70 @@
+//! A local stand-in for GlazeWM's IPC server, used by tests.
+
+use std::net::TcpListener;
+
+use serde_json::json;
+use tungstenite::Message;
+
+/// Starts a single-connection server that answers every text message with
+/// the replies produced by `respond`.
+pub fn spawn(mut respond: impl FnMut(&str) -> Vec<serde_json::Value> + Send + 'static) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = tungstenite::accept(stream).unwrap();
+        while let Ok(Message::Text(text)) = socket.read() {
+            for reply in respond(&text) {
+                socket.send(Message::text(reply.to_string())).unwrap();
+            }
+        }
+    });
+
+    url
+}
+
+/// Starts a server that expects the given client messages in order,
+/// answers each with its replies and closes the connection afterwards.
+pub fn scripted(script: Vec<(&'static str, Vec<serde_json::Value>)>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = tungstenite::accept(stream).unwrap();
+        for (expected, replies) in script {
+            match socket.read() {
+                Ok(Message::Text(text)) => assert_eq!(text.as_str(), expected),
+                other => panic!("expected `{expected}`, got {other:?}"),
+            }
+            for reply in replies {
+                socket.send(Message::text(reply.to_string())).unwrap();
+            }
+        }
+        let _ = socket.close(None);
+        let _ = socket.flush();
+    });
+
+    url
+}
+
+pub fn response(message: &str, data: serde_json::Value) -> serde_json::Value {
+    json!({
+        "messageType": "client_response",
+        "clientMessage": message,
+        "data": data,
+        "error": null,
+        "success": true,
+    })
+}
+
+pub fn event(data: serde_json::Value) -> serde_json::Value {
+    json!({
+        "messageType": "event_subscription",
+        "subscriptionId": "stand-in",
+        "data": data,
+        "error": null,
+        "success": true,
+    })
+}
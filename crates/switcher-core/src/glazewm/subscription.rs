@@ -0,0 +1,565 @@
+//! Keeps the switcher state in sync with GlazeWM through event subscriptions.
+
+use std::time::{Duration, Instant};
+
+use super::dto::{
+    self, BindingModeDto, BindingModesDto, ContainerDto, MonitorDto, MonitorsDto, PausedDto,
+    WmEventDto, WorkspaceDto,
+};
+use super::ipc::IpcClient;
+use crate::state::State;
+
+/// Events that can change what the switcher displays.
+pub const EVENTS: &[&str] = &[
+    "focus_changed",
+    "workspace_activated",
+    "workspace_deactivated",
+    "workspace_updated",
+    "window_managed",
+    "window_unmanaged",
+    "monitor_added",
+    "monitor_removed",
+    "binding_modes_changed",
+    "pause_changed",
+    "tiling_direction_changed",
+];
+
+/// How often the whole state is re-read in case an event was missed.
+const RESYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What applying an event did to a [`Snapshot`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum EventEffect {
+    Changed,
+    Unchanged,
+    /// The event doesn't carry enough information, monitors must be re-queried.
+    NeedsRefresh,
+}
+
+/// GlazeWM's container tree as last reported, updated in place from events.
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    monitors: Vec<MonitorDto>,
+    binding_modes: Vec<BindingModeDto>,
+    paused: bool,
+}
+
+impl Snapshot {
+    pub fn query(client: &mut IpcClient) -> anyhow::Result<Self> {
+        let data: MonitorsDto = dto::parse(client.query("monitors")?)?;
+        let modes: BindingModesDto = dto::parse(client.query("binding-modes")?)?;
+        let paused: PausedDto = dto::parse(client.query("paused")?)?;
+        Ok(Self {
+            monitors: data.monitors,
+            binding_modes: modes.binding_modes,
+            paused: paused.paused,
+        })
+    }
+
+    pub fn to_state(&self) -> State {
+        State {
+            binding_modes: self
+                .binding_modes
+                .iter()
+                .map(BindingModeDto::to_state)
+                .collect(),
+            paused: self.paused,
+            ..dto::to_state(&self.monitors)
+        }
+    }
+
+    pub fn apply(&mut self, event: WmEventDto) -> EventEffect {
+        match event {
+            WmEventDto::WorkspaceActivated {
+                activated_workspace: workspace,
+            }
+            | WmEventDto::WorkspaceUpdated {
+                updated_workspace: workspace,
+            } => self.upsert(workspace),
+
+            WmEventDto::WorkspaceDeactivated { deactivated_id } => {
+                let mut removed = false;
+                for monitor in &mut self.monitors {
+                    let len = monitor.children.len();
+                    monitor.children.retain(|w| w.id != deactivated_id);
+                    removed |= monitor.children.len() != len;
+                }
+                Self::changed_if(removed)
+            }
+
+            WmEventDto::FocusChanged { focused_container } => match focused_container {
+                ContainerDto::Workspace(workspace) => self.upsert(workspace),
+                ContainerDto::Monitor(_) => EventEffect::NeedsRefresh,
+                container => self.focus(container.id()),
+            },
+
+            WmEventDto::WindowManaged { managed_window } => {
+                let inserted = self
+                    .workspaces_mut()
+                    .find(|w| {
+                        w.id == managed_window.parent_id || w.contains(&managed_window.parent_id)
+                    })
+                    .map(|w| w.insert_window(managed_window))
+                    .unwrap_or(false);
+
+                if inserted {
+                    EventEffect::Changed
+                } else {
+                    EventEffect::NeedsRefresh
+                }
+            }
+
+            WmEventDto::WindowUnmanaged { unmanaged_id } => {
+                let removed = self.workspaces_mut().any(|w| w.remove(&unmanaged_id));
+                Self::changed_if(removed)
+            }
+
+            WmEventDto::BindingModesChanged { new_binding_modes } => {
+                self.binding_modes = new_binding_modes;
+                EventEffect::Changed
+            }
+
+            WmEventDto::PauseChanged { is_paused } => {
+                let changed = self.paused != is_paused;
+                self.paused = is_paused;
+                Self::changed_if(changed)
+            }
+
+            WmEventDto::TilingDirectionChanged {
+                direction_container,
+                new_tiling_direction,
+            } => {
+                // splits aren't tracked, only the workspace direction is shown
+                let ContainerDto::Workspace(changed) = direction_container else {
+                    return EventEffect::Unchanged;
+                };
+
+                let workspace = self.workspaces_mut().find(|w| w.id == changed.id);
+                match workspace {
+                    Some(w) if w.tiling_direction != Some(new_tiling_direction) => {
+                        w.tiling_direction = Some(new_tiling_direction);
+                        EventEffect::Changed
+                    }
+                    Some(_) => EventEffect::Unchanged,
+                    None => EventEffect::NeedsRefresh,
+                }
+            }
+
+            WmEventDto::MonitorAdded | WmEventDto::MonitorRemoved => EventEffect::NeedsRefresh,
+
+            WmEventDto::Other => EventEffect::Unchanged,
+        }
+    }
+
+    fn changed_if(changed: bool) -> EventEffect {
+        if changed {
+            EventEffect::Changed
+        } else {
+            EventEffect::Unchanged
+        }
+    }
+
+    fn workspaces_mut(&mut self) -> impl Iterator<Item = &mut WorkspaceDto> {
+        self.monitors.iter_mut().flat_map(|m| m.children.iter_mut())
+    }
+
+    fn upsert(&mut self, workspace: WorkspaceDto) -> EventEffect {
+        if !self.monitors.iter().any(|m| m.id == workspace.parent_id) {
+            return EventEffect::NeedsRefresh;
+        }
+
+        for monitor in &mut self.monitors {
+            // a workspace may have been moved to another monitor
+            monitor.children.retain(|w| w.id != workspace.id);
+
+            for other in &mut monitor.children {
+                // Only one workspace holds focus, and only one is displayed per monitor.
+                if workspace.has_focus {
+                    other.has_focus = false;
+                }
+                if workspace.is_displayed && other.parent_id == workspace.parent_id {
+                    other.is_displayed = false;
+                }
+            }
+        }
+
+        if let Some(monitor) = self
+            .monitors
+            .iter_mut()
+            .find(|m| m.id == workspace.parent_id)
+        {
+            monitor.children.push(workspace);
+        }
+
+        EventEffect::Changed
+    }
+
+    /// Moves focus to the workspace holding the container with `id`.
+    fn focus(&mut self, id: &str) -> EventEffect {
+        let Some(workspace) = self
+            .monitors
+            .iter()
+            .flat_map(|m| &m.children)
+            .find(|w| w.contains(id))
+        else {
+            return EventEffect::NeedsRefresh;
+        };
+
+        let mut workspace = workspace.clone();
+        workspace.has_focus = true;
+        workspace.is_displayed = true;
+        workspace.focus(id);
+        self.upsert(workspace)
+    }
+}
+
+/// Streams state updates from the GlazeWM IPC server at `url` into
+/// `on_state` until the connection fails.
+pub fn watch(url: &str, mut on_state: impl FnMut(State)) -> anyhow::Result<()> {
+    let mut client = super::connect(url)?;
+    client.subscribe(EVENTS)?;
+
+    let mut snapshot = Snapshot::query(&mut client)?;
+    let mut last_sync = Instant::now();
+    on_state(snapshot.to_state());
+
+    loop {
+        let effect = if last_sync.elapsed() >= RESYNC_INTERVAL {
+            tracing::debug!(target: "glazewm", "Periodic full resync");
+            EventEffect::NeedsRefresh
+        } else {
+            match client.next_event()? {
+                Some(event) => snapshot.apply(dto::parse(event)?),
+                None => continue,
+            }
+        };
+
+        match effect {
+            EventEffect::Changed => on_state(snapshot.to_state()),
+            EventEffect::NeedsRefresh => {
+                snapshot = Snapshot::query(&mut client)?;
+                last_sync = Instant::now();
+                on_state(snapshot.to_state());
+            }
+            EventEffect::Unchanged => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::glazewm::stand_in::{self, event, response};
+    use crate::state::TilingDirection;
+
+    fn workspace(id: &str, focused: bool, children: Value) -> Value {
+        json!({
+            "type": "workspace",
+            "id": id,
+            "name": id,
+            "parentId": "m",
+            "hasFocus": focused,
+            "isDisplayed": focused,
+            "tilingDirection": "horizontal",
+            "children": children,
+        })
+    }
+
+    fn monitors(workspaces: Vec<Value>) -> Value {
+        json!({
+            "monitors": [{
+                "type": "monitor",
+                "id": "m",
+                "children": workspaces,
+                "x": 0,
+                "y": 0,
+                "width": 1920,
+                "height": 1080,
+                "dpi": 96,
+                "deviceName": "\\\\.\\DISPLAY1",
+                "hardwareId": null,
+            }]
+        })
+    }
+
+    fn snapshot(workspaces: Vec<Value>) -> Snapshot {
+        let data: MonitorsDto = dto::parse(monitors(workspaces)).unwrap();
+        Snapshot {
+            monitors: data.monitors,
+            binding_modes: Vec::new(),
+            paused: false,
+        }
+    }
+
+    fn apply(snapshot: &mut Snapshot, event: Value) -> EventEffect {
+        snapshot.apply(dto::parse(event).unwrap())
+    }
+
+    /// Workspace names with their focused and empty flags.
+    fn summary(state: &State) -> Vec<(String, bool, bool)> {
+        state
+            .monitors
+            .iter()
+            .flat_map(|m| &m.workspaces)
+            .map(|w| (w.name.clone(), w.focused, w.is_empty))
+            .collect()
+    }
+
+    fn ws(name: &str, focused: bool, is_empty: bool) -> (String, bool, bool) {
+        (name.into(), focused, is_empty)
+    }
+
+    #[test]
+    fn activated_workspace_takes_focus() {
+        let mut snapshot = snapshot(vec![
+            workspace("1", true, json!([])),
+            workspace("2", false, json!([])),
+        ]);
+
+        let effect = apply(
+            &mut snapshot,
+            json!({
+                "eventType": "workspace_activated",
+                "activatedWorkspace": workspace("3", true, json!([])),
+            }),
+        );
+
+        assert_eq!(effect, EventEffect::Changed);
+        assert_eq!(
+            summary(&snapshot.to_state()),
+            [
+                ws("1", false, true),
+                ws("2", false, true),
+                ws("3", true, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn tiling_direction_follows_workspace_changes() {
+        let mut snapshot = snapshot(vec![workspace("1", true, json!([]))]);
+        let direction =
+            |snapshot: &Snapshot| snapshot.to_state().monitors[0].workspaces[0].tiling_direction;
+        assert_eq!(direction(&snapshot), Some(TilingDirection::Horizontal));
+
+        let split = json!({ "type": "split", "id": "s", "children": [] });
+        let event = |container: Value| {
+            json!({
+                "eventType": "tiling_direction_changed",
+                "directionContainer": container,
+                "newTilingDirection": "vertical",
+            })
+        };
+
+        assert_eq!(apply(&mut snapshot, event(split)), EventEffect::Unchanged);
+        assert_eq!(
+            apply(&mut snapshot, event(workspace("1", true, json!([])))),
+            EventEffect::Changed
+        );
+        assert_eq!(direction(&snapshot), Some(TilingDirection::Vertical));
+    }
+
+    #[test]
+    fn deactivated_workspace_is_removed() {
+        let mut snapshot = snapshot(vec![
+            workspace("1", true, json!([])),
+            workspace("2", false, json!([])),
+        ]);
+
+        let event = json!({
+            "eventType": "workspace_deactivated",
+            "deactivatedId": "2",
+            "deactivatedName": "2",
+        });
+
+        assert_eq!(apply(&mut snapshot, event.clone()), EventEffect::Changed);
+        assert_eq!(apply(&mut snapshot, event), EventEffect::Unchanged);
+        assert_eq!(summary(&snapshot.to_state()), [ws("1", true, true)]);
+    }
+
+    #[test]
+    fn window_events_update_emptiness() {
+        let split = json!([{ "type": "split", "id": "s", "children": [] }]);
+        let mut snapshot = snapshot(vec![workspace("1", true, split)]);
+
+        let effect = apply(
+            &mut snapshot,
+            json!({
+                "eventType": "window_managed",
+                "managedWindow": { "type": "window", "id": "w", "parentId": "s" },
+            }),
+        );
+        assert_eq!(effect, EventEffect::Changed);
+        assert_eq!(summary(&snapshot.to_state()), [ws("1", true, false)]);
+
+        let effect = apply(
+            &mut snapshot,
+            json!({
+                "eventType": "window_unmanaged",
+                "unmanagedId": "w",
+                "unmanagedHandle": 1,
+            }),
+        );
+        assert_eq!(effect, EventEffect::Changed);
+        assert_eq!(summary(&snapshot.to_state()), [ws("1", true, true)]);
+    }
+
+    #[test]
+    fn focusing_a_window_displays_its_workspace() {
+        let window = json!([{ "type": "window", "id": "w", "parentId": "2" }]);
+        let mut snapshot = snapshot(vec![
+            workspace("1", true, json!([])),
+            workspace("2", false, window.clone()),
+        ]);
+
+        let effect = apply(
+            &mut snapshot,
+            json!({
+                "eventType": "focus_changed",
+                "focusedContainer": { "type": "window", "id": "w", "parentId": "2" },
+            }),
+        );
+
+        assert_eq!(effect, EventEffect::Changed);
+        assert_eq!(
+            summary(&snapshot.to_state()),
+            [ws("1", false, true), ws("2", true, false)]
+        );
+    }
+
+    #[test]
+    fn unknown_window_focus_needs_refresh() {
+        let mut snapshot = snapshot(vec![workspace("1", true, json!([]))]);
+        let effect = apply(
+            &mut snapshot,
+            json!({
+                "eventType": "focus_changed",
+                "focusedContainer": { "type": "window", "id": "w", "parentId": "x" },
+            }),
+        );
+
+        assert_eq!(effect, EventEffect::NeedsRefresh);
+    }
+
+    #[test]
+    fn watch_follows_scripted_events() {
+        let sub = "sub --events focus_changed workspace_activated workspace_deactivated \
+                   workspace_updated window_managed window_unmanaged monitor_added monitor_removed \
+                   binding_modes_changed pause_changed tiling_direction_changed";
+        let url = stand_in::scripted(vec![
+            (
+                "query app-metadata",
+                vec![response(
+                    "query app-metadata",
+                    json!({ "version": "3.1.0" }),
+                )],
+            ),
+            (sub, vec![response(sub, json!({ "subscriptionId": "s" }))]),
+            (
+                "query monitors",
+                vec![response(
+                    "query monitors",
+                    monitors(vec![
+                        workspace("1", true, json!([])),
+                        workspace("2", false, json!([])),
+                    ]),
+                )],
+            ),
+            (
+                "query binding-modes",
+                vec![response(
+                    "query binding-modes",
+                    json!({ "bindingModes": [] }),
+                )],
+            ),
+            (
+                "query paused",
+                vec![
+                    response("query paused", json!({ "paused": false })),
+                    event(json!({
+                        "eventType": "workspace_activated",
+                        "activatedWorkspace": workspace("3", true, json!([])),
+                    })),
+                    event(json!({
+                        "eventType": "workspace_deactivated",
+                        "deactivatedId": "2",
+                        "deactivatedName": "2",
+                    })),
+                    event(json!({
+                        "eventType": "binding_modes_changed",
+                        "newBindingModes": [{ "name": "resize", "displayName": "Resize" }],
+                    })),
+                    event(json!({ "eventType": "pause_changed", "isPaused": true })),
+                    event(json!({ "eventType": "monitor_added", "addedMonitor": {} })),
+                ],
+            ),
+            (
+                "query monitors",
+                vec![response(
+                    "query monitors",
+                    monitors(vec![
+                        workspace("1", true, json!([])),
+                        workspace("3", false, json!([])),
+                    ]),
+                )],
+            ),
+            (
+                "query binding-modes",
+                vec![response(
+                    "query binding-modes",
+                    json!({ "bindingModes": [{ "name": "resize", "displayName": null }] }),
+                )],
+            ),
+            (
+                "query paused",
+                vec![response("query paused", json!({ "paused": true }))],
+            ),
+        ]);
+
+        let mut states = Vec::new();
+        let mut modes = Vec::new();
+        let mut paused = Vec::new();
+        let result = watch(&url, |state| {
+            states.push(summary(&state));
+            paused.push(state.paused);
+            modes.push(
+                state
+                    .binding_modes
+                    .iter()
+                    .map(|m| m.label().to_string())
+                    .collect::<Vec<_>>(),
+            );
+        });
+
+        assert!(result.is_err(), "watch should end when the server hangs up");
+        assert_eq!(
+            states,
+            [
+                vec![ws("1", true, true), ws("2", false, true)],
+                vec![
+                    ws("1", false, true),
+                    ws("2", false, true),
+                    ws("3", true, true)
+                ],
+                vec![ws("1", false, true), ws("3", true, true)],
+                vec![ws("1", false, true), ws("3", true, true)],
+                vec![ws("1", false, true), ws("3", true, true)],
+                vec![ws("1", true, true), ws("3", false, true)],
+            ]
+        );
+        assert_eq!(
+            modes,
+            [
+                vec![],
+                vec![],
+                vec![],
+                vec!["Resize"],
+                vec!["Resize"],
+                vec!["resize"]
+            ]
+        );
+        assert_eq!(paused, [false, false, false, false, true, true]);
+    }
+}
@@ -0,0 +1,277 @@
+//! Typed models of the komorebi state, limited to what the switcher displays.
+
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::Deserialize;
+
+/// Deserializes a komorebi payload, naming the offending field on failure.
+pub fn parse<T: DeserializeOwned>(text: &str) -> anyhow::Result<T> {
+    let de = &mut serde_json::Deserializer::from_str(text);
+    serde_path_to_error::deserialize(de).map_err(|e| {
+        let path = e.path().to_string();
+        anyhow::anyhow!("Invalid komorebi payload at `{path}`: {}", e.into_inner())
+    })
+}
+
+/// What komorebi pushes to subscriber sockets.
+#[derive(Debug, Deserialize)]
+pub struct NotificationDto {
+    pub state: StateDto,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StateDto {
+    pub monitors: RingDto<MonitorDto>,
+    #[serde(default)]
+    pub mouse_follows_focus: bool,
+    #[serde(default)]
+    pub is_paused: bool,
+}
+
+/// komorebi's list with a focused element.
+#[derive(Debug, Deserialize)]
+pub struct RingDto<T> {
+    pub elements: Vec<T>,
+    #[serde(default)]
+    pub focused: usize,
+}
+
+impl<T> Default for RingDto<T> {
+    fn default() -> Self {
+        Self {
+            elements: Vec::new(),
+            focused: 0,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MonitorDto {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub device_id: String,
+    #[serde(default)]
+    pub serial_number_id: Option<String>,
+    pub size: RectDto,
+    pub workspaces: RingDto<WorkspaceDto>,
+}
+
+/// komorebi stores the width and height in `right` and `bottom`.
+#[derive(Debug, Deserialize)]
+pub struct RectDto {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkspaceDto {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub containers: RingDto<ContainerDto>,
+    #[serde(default)]
+    pub monocle_container: Option<ContainerDto>,
+    #[serde(default)]
+    pub maximized_window: Option<IgnoredAny>,
+    #[serde(default)]
+    pub floating_windows: WindowsDto,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContainerDto {
+    pub windows: RingDto<IgnoredAny>,
+}
+
+/// Floating windows are a plain list before komorebi v0.1.36 and a ring since.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum WindowsDto {
+    List(Vec<IgnoredAny>),
+    Ring(RingDto<IgnoredAny>),
+}
+
+impl Default for WindowsDto {
+    fn default() -> Self {
+        Self::List(Vec::new())
+    }
+}
+
+impl WindowsDto {
+    fn len(&self) -> usize {
+        match self {
+            Self::List(windows) => windows.len(),
+            Self::Ring(windows) => windows.elements.len(),
+        }
+    }
+}
+
+impl WorkspaceDto {
+    /// Tiled, monocle, maximized and floating windows on this workspace.
+    fn window_count(&self) -> usize {
+        let tiled = self.containers.elements.iter();
+        tiled
+            .chain(&self.monocle_container)
+            .map(|c| c.windows.elements.len())
+            .sum::<usize>()
+            + usize::from(self.maximized_window.is_some())
+            + self.floating_windows.len()
+    }
+}
+
+impl MonitorDto {
+    /// Unnamed workspaces are shown, and addressed, by their 1-based number.
+    fn workspace_name(workspace: &WorkspaceDto, idx: usize) -> String {
+        workspace
+            .name
+            .clone()
+            .unwrap_or_else(|| (idx + 1).to_string())
+    }
+
+    /// Index of the workspace shown as `name`.
+    pub fn workspace_index(&self, name: &str) -> Option<usize> {
+        self.workspaces
+            .elements
+            .iter()
+            .enumerate()
+            .position(|(idx, w)| Self::workspace_name(w, idx) == name)
+    }
+
+    fn to_state(&self, index: usize) -> crate::state::Monitor {
+        let workspaces = self
+            .workspaces
+            .elements
+            .iter()
+            .enumerate()
+            .map(|(idx, w)| {
+                let window_count = w.window_count();
+                crate::state::Workspace {
+                    name: Self::workspace_name(w, idx),
+                    focused: idx == self.workspaces.focused,
+                    is_empty: window_count == 0,
+                    window_count,
+                    windows: Vec::new(),
+                    tiling_direction: None,
+                }
+            })
+            .collect();
+
+        crate::state::Monitor {
+            name: crate::state::display_name(&self.name),
+            index,
+            id: self
+                .serial_number_id
+                .clone()
+                .unwrap_or_else(|| self.device_id.clone()),
+            workspaces,
+            rect: crate::state::Rect {
+                left: self.size.left,
+                top: self.size.top,
+                right: self.size.left + self.size.right,
+                bottom: self.size.top + self.size.bottom,
+            },
+            // komorebi doesn't report it
+            dpi: 0,
+        }
+    }
+}
+
+impl StateDto {
+    pub fn to_state(&self) -> crate::state::State {
+        crate::state::State {
+            monitors: self
+                .monitors
+                .elements
+                .iter()
+                .enumerate()
+                .map(|(index, monitor)| monitor.to_state(index))
+                .collect(),
+            paused: self.is_paused,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn maps_komorebi_state() {
+        let state = json!({
+            "monitors": {
+                "elements": [{
+                    "id": 65537,
+                    "name": "DISPLAY2",
+                    "device": "DEL40F6",
+                    "device_id": "DEL40F6-5&2a8e1f&0&UID4353",
+                    "serial_number_id": "H4CR800751P",
+                    "size": { "left": 1920, "top": 0, "right": 2560, "bottom": 1440 },
+                    "workspaces": {
+                        "elements": [
+                            {
+                                "name": "web",
+                                "containers": {
+                                    "elements": [{ "windows": { "elements": [{ "hwnd": 1 }, { "hwnd": 3 }], "focused": 0 } }],
+                                    "focused": 0,
+                                },
+                                "maximized_window": { "hwnd": 4 },
+                                "floating_windows": [{ "hwnd": 5 }],
+                            },
+                            { "name": null, "containers": { "elements": [], "focused": 0 }, "floating_windows": [] },
+                            {
+                                "name": null,
+                                "containers": { "elements": [], "focused": 0 },
+                                "floating_windows": { "elements": [{ "hwnd": 2 }], "focused": 0 },
+                            },
+                        ],
+                        "focused": 1,
+                    },
+                }],
+                "focused": 0,
+            },
+            "mouse_follows_focus": true,
+            "is_paused": true,
+        });
+
+        let dto = parse::<StateDto>(&state.to_string()).unwrap();
+        assert!(dto.mouse_follows_focus);
+        assert_eq!(dto.monitors.elements[0].workspace_index("3"), Some(2));
+
+        let state = dto.to_state();
+        assert!(state.paused);
+
+        let monitor = &state.monitors[0];
+        assert_eq!(monitor.name, "Display 2");
+        assert_eq!(monitor.id, "H4CR800751P");
+        assert_eq!(
+            (monitor.rect.left, monitor.rect.right, monitor.rect.bottom),
+            (1920, 4480, 1440)
+        );
+
+        let workspaces = monitor
+            .workspaces
+            .iter()
+            .map(|w| (w.name.as_str(), w.focused, w.is_empty, w.window_count))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            workspaces,
+            [
+                ("web", false, false, 4),
+                ("2", true, true, 0),
+                ("3", false, false, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_error_names_the_failing_field() {
+        let error = parse::<StateDto>(r#"{ "monitors": { "elements": [{ "size": {} }] } }"#)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("monitors.elements[0].size"), "{error}");
+    }
+}
@@ -0,0 +1,407 @@
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+
+use crate::command_line::split_args;
+use crate::provider::{MenuCommand, WorkspaceAction, WorkspaceProvider};
+use crate::state::State;
+
+mod dto;
+mod socket;
+#[cfg(test)]
+mod stand_in;
+
+use socket::{SocketMessage, Sockets};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// How often the whole state is re-read and the subscription renewed, so a
+/// restarted komorebi keeps notifying us.
+const RESYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// `komorebic` commands offered in the context menu.
+const MENU_COMMANDS: &[MenuCommand] = &[
+    MenuCommand {
+        label: "Reload config",
+        command: "reload-configuration",
+    },
+    MenuCommand {
+        label: "Retile",
+        command: "retile",
+    },
+    MenuCommand {
+        label: "Toggle floating",
+        command: "toggle-float",
+    },
+    MenuCommand {
+        label: "Toggle maximize",
+        command: "toggle-maximize",
+    },
+    MenuCommand {
+        label: "Minimize",
+        command: "minimize",
+    },
+    MenuCommand {
+        label: "Stop komorebi",
+        command: "stop",
+    },
+];
+
+/// Actions offered in the context menu of a workspace.
+const WORKSPACE_ACTIONS: &[WorkspaceAction] = &[
+    WorkspaceAction::Focus,
+    WorkspaceAction::MoveFocusedWindowHere,
+    WorkspaceAction::MoveFocusedWindowAndFollow,
+    WorkspaceAction::MoveToNextMonitor,
+    WorkspaceAction::MoveToPreviousMonitor,
+];
+
+/// The komorebi backend, talking to its socket.
+pub struct Komorebi {
+    sockets: Sockets,
+}
+
+impl Komorebi {
+    /// Talks to komorebi through the sockets in `data_dir`, komorebi's own
+    /// data directory when `None`.
+    pub fn new(data_dir: Option<PathBuf>) -> Self {
+        let sockets = match data_dir {
+            Some(dir) => Sockets::new(dir),
+            None => Sockets::from_env(),
+        };
+        Self::with_sockets(sockets)
+    }
+
+    fn with_sockets(sockets: Sockets) -> Self {
+        Self { sockets }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.read_state().is_ok()
+    }
+
+    fn read_state(&self) -> anyhow::Result<dto::StateDto> {
+        dto::parse(&self.sockets.query(&SocketMessage::State)?)
+    }
+
+    /// Moves a workspace to the monitor at `target_monitor_idx`. komorebi
+    /// moves the focused workspace, so the workspace is focused first.
+    fn move_workspace(
+        &self,
+        state: &dto::StateDto,
+        monitor_idx: usize,
+        workspace_name: &str,
+        target_monitor_idx: usize,
+    ) -> anyhow::Result<()> {
+        let workspace_idx = Self::workspace_index(state, monitor_idx, workspace_name)?;
+        self.sockets.send(&[
+            SocketMessage::FocusMonitorWorkspaceNumber(monitor_idx, workspace_idx),
+            SocketMessage::MoveWorkspaceToMonitorNumber(target_monitor_idx),
+        ])
+    }
+
+    /// Index of the workspace named `workspace_name` on the monitor at `monitor_idx`.
+    fn workspace_index(
+        state: &dto::StateDto,
+        monitor_idx: usize,
+        workspace_name: &str,
+    ) -> anyhow::Result<usize> {
+        state
+            .monitors
+            .elements
+            .get(monitor_idx)
+            .and_then(|m| m.workspace_index(workspace_name))
+            .with_context(|| {
+                format!("Workspace {workspace_name:?} is not on monitor {monitor_idx}")
+            })
+    }
+}
+
+impl WorkspaceProvider for Komorebi {
+    fn name(&self) -> &str {
+        "komorebi"
+    }
+
+    fn snapshot(&self) -> anyhow::Result<State> {
+        Ok(self.read_state()?.to_state())
+    }
+
+    fn watch(&self, sink: &mut dyn FnMut(State)) -> anyhow::Result<()> {
+        let subscriber = self.sockets.subscribe(socket::SUBSCRIBER_NAME)?;
+        sink(self.read_state()?.to_state());
+        let mut last_sync = Instant::now();
+
+        loop {
+            let until_resync = RESYNC_INTERVAL.saturating_sub(last_sync.elapsed());
+            if until_resync.is_zero() {
+                tracing::debug!(target: "komorebi", "Periodic full resync");
+                let name = socket::SUBSCRIBER_NAME.to_string();
+                self.sockets
+                    .send(&[SocketMessage::AddSubscriberSocket(name)])?;
+                sink(self.read_state()?.to_state());
+                last_sync = Instant::now();
+                continue;
+            }
+
+            if let Some(text) = subscriber.next_notification(until_resync)? {
+                let notification: dto::NotificationDto = dto::parse(&text)?;
+                sink(notification.state.to_state());
+            }
+        }
+    }
+
+    /// komorebi always focuses the monitor of the workspace it switches to.
+    /// Without `focus_monitor`, the cursor is kept in place instead of
+    /// following the focus there.
+    fn activate_workspace(
+        &self,
+        monitor_idx: usize,
+        workspace_name: &str,
+        focus_monitor: bool,
+    ) -> anyhow::Result<()> {
+        let state = self.read_state()?;
+        let workspace_idx = Self::workspace_index(&state, monitor_idx, workspace_name)?;
+
+        let focus = SocketMessage::FocusMonitorWorkspaceNumber(monitor_idx, workspace_idx);
+        if state.mouse_follows_focus && !focus_monitor {
+            self.sockets.send(&[
+                SocketMessage::MouseFollowsFocus(false),
+                focus,
+                SocketMessage::MouseFollowsFocus(true),
+            ])
+        } else {
+            self.sockets.send(&[focus])
+        }
+    }
+
+    fn workspace_actions(&self) -> &[WorkspaceAction] {
+        WORKSPACE_ACTIONS
+    }
+
+    fn run_workspace_action(
+        &self,
+        monitor_idx: usize,
+        workspace_name: &str,
+        action: WorkspaceAction,
+        focus_monitor: bool,
+    ) -> anyhow::Result<()> {
+        match action {
+            WorkspaceAction::Focus => {
+                self.activate_workspace(monitor_idx, workspace_name, focus_monitor)
+            }
+            // komorebi's "send" leaves the focus in place, "move" follows the window
+            WorkspaceAction::MoveFocusedWindowHere
+            | WorkspaceAction::MoveFocusedWindowAndFollow => {
+                let state = self.read_state()?;
+                let workspace_idx = Self::workspace_index(&state, monitor_idx, workspace_name)?;
+                let message = if action == WorkspaceAction::MoveFocusedWindowHere {
+                    SocketMessage::SendContainerToMonitorWorkspaceNumber(monitor_idx, workspace_idx)
+                } else {
+                    SocketMessage::MoveContainerToMonitorWorkspaceNumber(monitor_idx, workspace_idx)
+                };
+                self.sockets.send(&[message])
+            }
+            // the monitor list wraps around at either end
+            WorkspaceAction::MoveToNextMonitor | WorkspaceAction::MoveToPreviousMonitor => {
+                let state = self.read_state()?;
+                let count = state.monitors.elements.len().max(1);
+                let target_monitor_idx = if action == WorkspaceAction::MoveToNextMonitor {
+                    (monitor_idx + 1) % count
+                } else {
+                    (monitor_idx + count - 1) % count
+                };
+                self.move_workspace(&state, monitor_idx, workspace_name, target_monitor_idx)
+            }
+            _ => anyhow::bail!("komorebi doesn't support {:?}", action.label()),
+        }
+    }
+
+    fn move_workspace_to_monitor(
+        &self,
+        monitor_idx: usize,
+        workspace_name: &str,
+        target_monitor_idx: usize,
+    ) -> anyhow::Result<()> {
+        let state = self.read_state()?;
+        self.move_workspace(&state, monitor_idx, workspace_name, target_monitor_idx)
+    }
+
+    fn supports_pause(&self) -> bool {
+        true
+    }
+
+    fn toggle_pause(&self) -> anyhow::Result<()> {
+        self.sockets.send(&[SocketMessage::TogglePause])
+    }
+
+    /// Runs `komorebic <command>`.
+    fn run_command(&self, command: &str) -> anyhow::Result<()> {
+        let mut cmd = Command::new("komorebic");
+        cmd.args(split_args(command)?)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        #[cfg(windows)]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+
+        let status = cmd
+            .status()
+            .with_context(|| format!("failed to invoke komorebic {command}"))?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("komorebic {command} failed"))
+        }
+    }
+
+    fn menu_commands(&self) -> &[MenuCommand] {
+        MENU_COMMANDS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn state() -> String {
+        let workspace = |name: Option<&str>| json!({ "name": name, "containers": { "elements": [], "focused": 0 }, "floating_windows": [] });
+        let monitor = |workspaces| {
+            json!({
+                "name": "DISPLAY1",
+                "device_id": "DEL40F6",
+                "size": { "left": 0, "top": 0, "right": 1920, "bottom": 1080 },
+                "workspaces": { "elements": workspaces, "focused": 0 },
+            })
+        };
+
+        json!({
+            "monitors": {
+                "elements": [
+                    monitor(vec![workspace(None)]),
+                    monitor(vec![workspace(Some("web")), workspace(None)]),
+                ],
+                "focused": 0,
+            },
+            "mouse_follows_focus": true,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn activates_workspace_by_name_on_its_monitor() {
+        let (sockets, messages) =
+            stand_in::spawn(|message| (message == r#"{"type":"State"}"#).then(state));
+        let komorebi = Komorebi::with_sockets(sockets);
+
+        komorebi.activate_workspace(1, "2", false).unwrap();
+        assert!(komorebi.activate_workspace(0, "web", true).is_err());
+
+        assert_eq!(
+            messages.iter().take(5).collect::<Vec<_>>(),
+            [
+                r#"{"type":"State"}"#,
+                r#"{"type":"MouseFollowsFocus","content":false}"#,
+                r#"{"type":"FocusMonitorWorkspaceNumber","content":[1,1]}"#,
+                r#"{"type":"MouseFollowsFocus","content":true}"#,
+                r#"{"type":"State"}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn focus_monitor_lets_the_cursor_follow() {
+        let (sockets, messages) =
+            stand_in::spawn(|message| (message == r#"{"type":"State"}"#).then(state));
+        let komorebi = Komorebi::with_sockets(sockets);
+
+        komorebi.activate_workspace(1, "web", true).unwrap();
+
+        assert_eq!(
+            messages.iter().take(2).collect::<Vec<_>>(),
+            [
+                r#"{"type":"State"}"#,
+                r#"{"type":"FocusMonitorWorkspaceNumber","content":[1,0]}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn moves_workspace_to_another_monitor() {
+        let (sockets, messages) =
+            stand_in::spawn(|message| (message == r#"{"type":"State"}"#).then(state));
+        let komorebi = Komorebi::with_sockets(sockets);
+
+        komorebi.move_workspace_to_monitor(1, "web", 0).unwrap();
+
+        assert_eq!(
+            messages.iter().take(3).collect::<Vec<_>>(),
+            [
+                r#"{"type":"State"}"#,
+                r#"{"type":"FocusMonitorWorkspaceNumber","content":[1,0]}"#,
+                r#"{"type":"MoveWorkspaceToMonitorNumber","content":0}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn moves_focused_window_to_workspace() {
+        let (sockets, messages) =
+            stand_in::spawn(|message| (message == r#"{"type":"State"}"#).then(state));
+        let komorebi = Komorebi::with_sockets(sockets);
+
+        let action = WorkspaceAction::MoveFocusedWindowHere;
+        komorebi
+            .run_workspace_action(1, "web", action, false)
+            .unwrap();
+        let action = WorkspaceAction::MoveFocusedWindowAndFollow;
+        komorebi
+            .run_workspace_action(1, "web", action, false)
+            .unwrap();
+
+        assert_eq!(
+            messages.iter().take(4).collect::<Vec<_>>(),
+            [
+                r#"{"type":"State"}"#,
+                r#"{"type":"SendContainerToMonitorWorkspaceNumber","content":[1,0]}"#,
+                r#"{"type":"State"}"#,
+                r#"{"type":"MoveContainerToMonitorWorkspaceNumber","content":[1,0]}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn moves_workspace_to_the_next_and_previous_monitor() {
+        let (sockets, messages) =
+            stand_in::spawn(|message| (message == r#"{"type":"State"}"#).then(state));
+        let komorebi = Komorebi::with_sockets(sockets);
+
+        let action = WorkspaceAction::MoveToNextMonitor;
+        komorebi
+            .run_workspace_action(1, "web", action, false)
+            .unwrap();
+        let action = WorkspaceAction::MoveToPreviousMonitor;
+        komorebi
+            .run_workspace_action(0, "1", action, false)
+            .unwrap();
+
+        assert_eq!(
+            messages.iter().take(6).collect::<Vec<_>>(),
+            [
+                r#"{"type":"State"}"#,
+                r#"{"type":"FocusMonitorWorkspaceNumber","content":[1,0]}"#,
+                r#"{"type":"MoveWorkspaceToMonitorNumber","content":0}"#,
+                r#"{"type":"State"}"#,
+                r#"{"type":"FocusMonitorWorkspaceNumber","content":[0,0]}"#,
+                r#"{"type":"MoveWorkspaceToMonitorNumber","content":1}"#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,211 @@
+//! komorebi's socket protocol: JSON messages are written to `komorebi.sock`
+//! and state notifications are pushed to a socket owned by the subscriber.
+
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use serde::Serialize;
+#[cfg(windows)]
+use uds_windows::{UnixListener, UnixStream};
+
+/// Name of the socket komorebi pushes notifications to.
+pub const SUBSCRIBER_NAME: &str = "glazewm-switcher.sock";
+
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The subset of komorebi's `SocketMessage` used by the switcher.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "content")]
+pub enum SocketMessage {
+    State,
+    AddSubscriberSocket(String),
+    FocusMonitorWorkspaceNumber(usize, usize),
+    MoveContainerToMonitorWorkspaceNumber(usize, usize),
+    SendContainerToMonitorWorkspaceNumber(usize, usize),
+    MoveWorkspaceToMonitorNumber(usize),
+    MouseFollowsFocus(bool),
+    TogglePause,
+}
+
+/// komorebi's data directory, where both its socket and subscriber sockets live.
+#[derive(Debug, Clone)]
+pub struct Sockets {
+    data_dir: PathBuf,
+}
+
+impl Sockets {
+    pub fn new(data_dir: PathBuf) -> Self {
+        Self { data_dir }
+    }
+
+    /// The data directory komorebi itself uses.
+    pub fn from_env() -> Self {
+        Self::new(dirs::data_local_dir().unwrap_or_default().join("komorebi"))
+    }
+
+    fn connect(&self) -> anyhow::Result<UnixStream> {
+        let path = self.data_dir.join("komorebi.sock");
+        let stream = UnixStream::connect(&path)
+            .with_context(|| format!("Failed to connect to komorebi at {}", path.display()))?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        Ok(stream)
+    }
+
+    /// Sends `messages` in one batch, one message per line.
+    pub fn send(&self, messages: &[SocketMessage]) -> anyhow::Result<()> {
+        let mut batch = String::new();
+        for message in messages {
+            batch.push_str(&serde_json::to_string(message)?);
+            batch.push('\n');
+        }
+
+        self.connect()?.write_all(batch.as_bytes())?;
+        Ok(())
+    }
+
+    /// Sends a query message and returns komorebi's reply.
+    pub fn query(&self, message: &SocketMessage) -> anyhow::Result<String> {
+        let mut stream = self.connect()?;
+        stream.write_all(format!("{}\n", serde_json::to_string(message)?).as_bytes())?;
+
+        let mut reply = String::new();
+        BufReader::new(stream)
+            .read_line(&mut reply)
+            .context("komorebi did not answer")?;
+        Ok(reply)
+    }
+
+    /// Creates the subscriber socket named `name` and asks komorebi to notify it.
+    pub fn subscribe(&self, name: &str) -> anyhow::Result<Subscriber> {
+        let path = self.data_dir.join(name);
+
+        // a stale socket file is left behind if the switcher was killed
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+
+        let listener = UnixListener::bind(&path)
+            .with_context(|| format!("Failed to create socket at {}", path.display()))?;
+        listener.set_nonblocking(true)?;
+        let subscriber = Subscriber { listener, path };
+
+        self.send(&[SocketMessage::AddSubscriberSocket(name.into())])?;
+
+        Ok(subscriber)
+    }
+}
+
+/// A socket komorebi connects to whenever its state changes.
+pub struct Subscriber {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl Subscriber {
+    /// Waits up to `timeout` for the next notification. Only the latest one is
+    /// returned when komorebi sends several at once, as each holds the whole state.
+    pub fn next_notification(&self, timeout: Duration) -> anyhow::Result<Option<String>> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    if let Some(notification) = Self::read_notification(stream)? {
+                        return Ok(Some(notification));
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    if Instant::now() >= deadline {
+                        return Ok(None);
+                    }
+                    std::thread::sleep(POLL_INTERVAL);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    fn read_notification(stream: UnixStream) -> anyhow::Result<Option<String>> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+        let mut text = String::new();
+        BufReader::new(stream).read_to_string(&mut text)?;
+
+        Ok(text
+            .lines()
+            .rfind(|line| !line.trim().is_empty())
+            .map(ToString::to_string))
+    }
+}
+
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::komorebi::stand_in;
+
+    #[test]
+    fn query_returns_reply() {
+        let (sockets, messages) =
+            stand_in::spawn(|_| Some(r#"{"monitors":{"elements":[]}}"#.into()));
+
+        let reply = sockets.query(&SocketMessage::State).unwrap();
+        assert_eq!(reply.trim(), r#"{"monitors":{"elements":[]}}"#);
+        assert_eq!(messages.recv().unwrap(), r#"{"type":"State"}"#);
+    }
+
+    #[test]
+    fn sends_batches_in_order() {
+        let (sockets, messages) = stand_in::spawn(|_| None);
+
+        sockets
+            .send(&[
+                SocketMessage::MouseFollowsFocus(false),
+                SocketMessage::FocusMonitorWorkspaceNumber(1, 2),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            messages.iter().take(2).collect::<Vec<_>>(),
+            [
+                r#"{"type":"MouseFollowsFocus","content":false}"#,
+                r#"{"type":"FocusMonitorWorkspaceNumber","content":[1,2]}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn subscriber_receives_latest_notification() {
+        let (sockets, messages) = stand_in::spawn(|_| None);
+
+        let subscriber = sockets.subscribe("test.sock").unwrap();
+        assert_eq!(
+            messages.recv().unwrap(),
+            r#"{"type":"AddSubscriberSocket","content":"test.sock"}"#
+        );
+        assert!(subscriber
+            .next_notification(Duration::from_millis(100))
+            .unwrap()
+            .is_none());
+
+        let mut komorebi = UnixStream::connect(&subscriber.path).unwrap();
+        komorebi.write_all(b"{\"n\":1}\n{\"n\":2}\n").unwrap();
+        drop(komorebi);
+
+        let notification = subscriber
+            .next_notification(Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(notification.as_deref(), Some(r#"{"n":2}"#));
+    }
+}
@@ -0,0 +1,58 @@
+//! A local stand-in for komorebi's socket, used by tests.
+
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver};
+
+#[cfg(windows)]
+use uds_windows::UnixListener;
+
+use super::socket::Sockets;
+
+/// Creates an empty directory to act as komorebi's data directory.
+pub fn data_dir() -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+    let dir = std::env::temp_dir().join(format!(
+        "switcher-komorebi-{}-{}",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Serves `komorebi.sock` in a fresh data directory, answering each message
+/// with the reply produced by `respond`. Received messages are forwarded to
+/// the returned receiver.
+pub fn spawn(
+    mut respond: impl FnMut(&str) -> Option<String> + Send + 'static,
+) -> (Sockets, Receiver<String>) {
+    let dir = data_dir();
+    let listener = UnixListener::bind(dir.join("komorebi.sock")).unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let reader = BufReader::new(stream.try_clone().unwrap());
+            for line in reader.lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if let Some(reply) = respond(&line) {
+                    let _ = stream.write_all(format!("{reply}\n").as_bytes());
+                }
+                if tx.send(line).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    (Sockets::new(dir), rx)
+}
@@ -0,0 +1,312 @@
+//! Labels of the workspace buttons, rendered from templates like
+//! `{index}: {display_name}[ ({window_count})]`.
+//!
+//! `{field}` is replaced by the value of a field of the workspace. Text in
+//! `[...]` is only shown when every field inside it has a value, that is a
+//! non-empty text or a count above zero. `{{`, `}}`, `[[` and `]]` stand for
+//! the brackets themselves.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::state::Workspace;
+
+/// A field of a workspace a label can show.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    /// Position of the workspace on its monitor, starting at 1.
+    Index,
+    Name,
+    /// The alias of the workspace, or its name if it has none.
+    DisplayName,
+    WindowCount,
+    /// Process name of the window focused last on the workspace.
+    FocusedApp,
+}
+
+impl Field {
+    const ALL: [Self; 5] = [
+        Self::Index,
+        Self::Name,
+        Self::DisplayName,
+        Self::WindowCount,
+        Self::FocusedApp,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Index => "index",
+            Self::Name => "name",
+            Self::DisplayName => "display_name",
+            Self::WindowCount => "window_count",
+            Self::FocusedApp => "focused_app",
+        }
+    }
+}
+
+/// What the fields of a label are filled from.
+#[derive(Clone, Copy, Debug)]
+pub struct LabelContext<'a> {
+    pub workspace: &'a Workspace,
+    /// Position of the workspace on its monitor, starting at 0.
+    pub index: usize,
+    pub alias: Option<&'a str>,
+}
+
+impl LabelContext<'_> {
+    /// The value of `field`, `None` when it has none.
+    fn value(&self, field: Field) -> Option<String> {
+        let workspace = self.workspace;
+        let value = match field {
+            Field::Index => (self.index + 1).to_string(),
+            Field::Name => workspace.name.clone(),
+            Field::DisplayName => self.alias.unwrap_or(&workspace.name).to_string(),
+            Field::WindowCount if workspace.window_count == 0 => return None,
+            Field::WindowCount => workspace.window_count.to_string(),
+            Field::FocusedApp => workspace
+                .windows
+                .iter()
+                .find(|w| w.focused)
+                .map(|w| w.process_name.clone())?,
+        };
+
+        Some(value).filter(|v| !v.is_empty())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Part {
+    Text(String),
+    Field(Field),
+    /// Parts shown only when all of their fields have a value.
+    Optional(Vec<Part>),
+}
+
+/// A parsed label template, see the [module docs](self).
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct LabelTemplate {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl Default for LabelTemplate {
+    fn default() -> Self {
+        Self {
+            source: "{display_name}".into(),
+            parts: vec![Part::Field(Field::DisplayName)],
+        }
+    }
+}
+
+impl LabelTemplate {
+    /// Renders the label of a workspace. Falls back to the workspace name when
+    /// the label would be blank, so the button never goes empty.
+    pub fn render(&self, ctx: &LabelContext) -> String {
+        let mut label = String::new();
+        render_parts(&self.parts, ctx, &mut label);
+
+        if label.trim().is_empty() {
+            ctx.workspace.name.clone()
+        } else {
+            label
+        }
+    }
+}
+
+/// Appends the rendered `parts` to `out`, returns `false` if a field had no
+/// value.
+fn render_parts(parts: &[Part], ctx: &LabelContext, out: &mut String) -> bool {
+    let mut complete = true;
+    for part in parts {
+        match part {
+            Part::Text(text) => out.push_str(text),
+            Part::Field(field) => match ctx.value(*field) {
+                Some(value) => out.push_str(&value),
+                None => complete = false,
+            },
+            Part::Optional(parts) => {
+                let mut optional = String::new();
+                if render_parts(parts, ctx, &mut optional) {
+                    out.push_str(&optional);
+                }
+            }
+        }
+    }
+    complete
+}
+
+impl FromStr for LabelTemplate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        // parts of the `[...]` segment being read
+        let mut optional: Option<Vec<Part>> = None;
+        let mut text = String::new();
+        let mut chars = s.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' | '}' | '[' | ']' if chars.peek() == Some(&c) => {
+                    chars.next();
+                    text.push(c);
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => anyhow::bail!("Invalid label `{s}`, `{{{name}` is not closed"),
+                        }
+                    }
+                    let field = Field::ALL
+                        .into_iter()
+                        .find(|f| f.name() == name.trim())
+                        .ok_or_else(|| {
+                            let names = Field::ALL.map(|f| format!("`{{{}}}`", f.name())).join(", ");
+                            anyhow::anyhow!(
+                                "Invalid label `{s}`, unknown field `{{{name}}}`, expected one of {names}"
+                            )
+                        })?;
+
+                    let target = optional.as_mut().unwrap_or(&mut parts);
+                    push_text(target, &mut text);
+                    target.push(Part::Field(field));
+                }
+                '[' if optional.is_some() => {
+                    anyhow::bail!("Invalid label `{s}`, `[...]` can't be nested")
+                }
+                '[' => {
+                    push_text(&mut parts, &mut text);
+                    optional = Some(Vec::new());
+                }
+                ']' => {
+                    let mut inner = optional
+                        .take()
+                        .with_context(|| format!("Invalid label `{s}`, `]` is not opened"))?;
+                    push_text(&mut inner, &mut text);
+                    parts.push(Part::Optional(inner));
+                }
+                '}' => {
+                    anyhow::bail!("Invalid label `{s}`, `}}` is not opened, use `}}}}` for a brace")
+                }
+                c => text.push(c),
+            }
+        }
+
+        if optional.is_some() {
+            anyhow::bail!("Invalid label `{s}`, `[` is not closed");
+        }
+        push_text(&mut parts, &mut text);
+
+        Ok(Self {
+            source: s.to_string(),
+            parts,
+        })
+    }
+}
+
+fn push_text(parts: &mut Vec<Part>, text: &mut String) {
+    if !text.is_empty() {
+        parts.push(Part::Text(std::mem::take(text)));
+    }
+}
+
+impl TryFrom<String> for LabelTemplate {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for LabelTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Window;
+
+    fn render(template: &str, workspace: &Workspace, alias: Option<&str>) -> String {
+        let template = template.parse::<LabelTemplate>().unwrap();
+        template.render(&LabelContext {
+            workspace,
+            index: 2,
+            alias,
+        })
+    }
+
+    fn workspace(window_count: usize) -> Workspace {
+        let window = |process_name: &str, focused: bool| Window {
+            process_name: process_name.into(),
+            focused,
+            ..Default::default()
+        };
+
+        Workspace {
+            name: "web".into(),
+            is_empty: window_count == 0,
+            window_count,
+            windows: [window("code", false), window("firefox", true)]
+                .into_iter()
+                .take(window_count)
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn renders_fields() {
+        let ws = workspace(2);
+        assert_eq!(render("{display_name}", &ws, None), "web");
+        assert_eq!(render("{display_name}", &ws, Some("🌐")), "🌐");
+        assert_eq!(
+            render(
+                "{index}: {name} {window_count} {focused_app}",
+                &ws,
+                Some("🌐")
+            ),
+            "3: web 2 firefox"
+        );
+        assert_eq!(render("{{{ index }}} [[x]]", &ws, None), "{3} [x]");
+    }
+
+    #[test]
+    fn hides_optional_segments_without_values() {
+        let template = "{display_name}[ ({window_count})][ - {focused_app}]";
+        assert_eq!(render(template, &workspace(2), None), "web (2) - firefox");
+        // the only window isn't the focused one
+        assert_eq!(render(template, &workspace(1), None), "web (1)");
+        assert_eq!(render(template, &workspace(0), None), "web");
+    }
+
+    #[test]
+    fn blank_labels_fall_back_to_the_name() {
+        assert_eq!(render("{focused_app}", &workspace(0), None), "web");
+        assert_eq!(render("[{window_count}] ", &workspace(0), None), "web");
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        let error = |s: &str| s.parse::<LabelTemplate>().unwrap_err().to_string();
+
+        assert!(error("{nmae}").contains("unknown field `{nmae}`"));
+        assert!(error("{name").contains("not closed"));
+        assert!(error("name}").contains("`}` is not opened"));
+        assert!(error("[{name}").contains("`[` is not closed"));
+        assert!(error("{name}]").contains("`]` is not opened"));
+        assert!(error("[a [b] c]").contains("can't be nested"));
+
+        let template = "{index}[ {window_count}]".parse::<LabelTemplate>().unwrap();
+        assert_eq!(template.to_string(), "{index}[ {window_count}]");
+    }
+}
@@ -0,0 +1,94 @@
+//! Placement of switchers on taskbars.
+
+use crate::state::{Monitor, Rect};
+
+/// Picks which of `taskbars` hosts the switcher of `monitor`: the one inside
+/// the monitor. When the monitor geometry is unknown, or no taskbar lies
+/// inside it, taskbars are matched to monitors in order, falling back to the
+/// `primary` taskbar and then to the first one.
+pub fn taskbar_for_monitor(
+    monitor: &Monitor,
+    taskbars: &[Rect],
+    primary: Option<usize>,
+) -> Option<usize> {
+    if let Some(idx) = taskbars.iter().position(|tb| monitor.rect.contains(tb)) {
+        return Some(idx);
+    }
+
+    tracing::warn!(
+        "Failed to find taskbar for monitor: {}-{} {:?}",
+        monitor.name,
+        monitor.id,
+        monitor.rect
+    );
+
+    if monitor.index < taskbars.len() {
+        Some(monitor.index)
+    } else {
+        primary.or((!taskbars.is_empty()).then_some(0))
+    }
+}
+
+/// The monitor holding the screen point `(x, y)`, in physical pixels.
+pub fn monitor_at(monitors: &[Monitor], x: i32, y: i32) -> Option<&Monitor> {
+    monitors.iter().find(|m| m.rect.contains_point(x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(left: i32, top: i32, right: i32, bottom: i32) -> Rect {
+        Rect {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    fn monitor(index: usize, rect: Rect) -> Monitor {
+        Monitor {
+            index,
+            rect,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn picks_taskbar_inside_monitor() {
+        let taskbars = [rect(0, 1032, 1920, 1080), rect(-1920, 1032, 0, 1080)];
+
+        let left = monitor(0, rect(-1920, 0, 0, 1080));
+        assert_eq!(taskbar_for_monitor(&left, &taskbars, Some(0)), Some(1));
+
+        let right = monitor(1, rect(0, 0, 1920, 1080));
+        assert_eq!(taskbar_for_monitor(&right, &taskbars, Some(0)), Some(0));
+    }
+
+    #[test]
+    fn unknown_geometry_matches_in_order() {
+        let taskbars = [rect(0, 1032, 1920, 1080), rect(1920, 1032, 3840, 1080)];
+
+        let second = monitor(1, Rect::default());
+        assert_eq!(taskbar_for_monitor(&second, &taskbars, Some(0)), Some(1));
+
+        let third = monitor(2, Rect::default());
+        assert_eq!(taskbar_for_monitor(&third, &taskbars, Some(1)), Some(1));
+        assert_eq!(taskbar_for_monitor(&third, &taskbars, None), Some(0));
+        assert_eq!(taskbar_for_monitor(&third, &[], None), None);
+    }
+
+    #[test]
+    fn finds_monitor_under_point() {
+        let monitors = [
+            monitor(0, rect(0, 0, 1920, 1080)),
+            monitor(1, rect(-1920, 0, 0, 1080)),
+        ];
+
+        let index = |x, y| monitor_at(&monitors, x, y).map(|m| m.index);
+        assert_eq!(index(0, 1079), Some(0));
+        assert_eq!(index(-1, 500), Some(1));
+        assert_eq!(index(1920, 500), None);
+    }
+}
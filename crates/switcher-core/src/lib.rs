@@ -0,0 +1,23 @@
+//! The platform independent part of the switcher: the state model, window
+//! manager backends, options, layout math, drag handling and the workspace
+//! buttons. The Win32 shell on top of it only hosts the switchers and
+//! forwards clicks.
+
+pub mod bindings;
+pub mod cli;
+mod command_line;
+pub mod connection;
+pub mod drag;
+pub mod external;
+pub mod glazewm;
+pub mod komorebi;
+pub mod label;
+pub mod layout;
+pub mod multi_map;
+pub mod options;
+pub mod provider;
+pub mod state;
+pub mod theme;
+pub mod workspace_button;
+
+pub use multi_map::MultiMap;
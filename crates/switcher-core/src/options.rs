@@ -0,0 +1,471 @@
+//! Options of the switcher, read from `config.toml` in the user config
+//! directory. `SWITCHER_*` environment variables override the file.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context;
+use serde::{Deserialize, Deserializer};
+
+use crate::bindings::MouseBindings;
+use crate::connection::Backoff;
+use crate::label::LabelTemplate;
+use crate::provider::ProviderKind;
+use crate::theme::Theme;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Options {
+    pub hide_empty_workspaces: bool,
+    pub hide_if_offline: bool,
+    pub enable_scroll_switching: bool,
+    /// Show how many windows each workspace holds.
+    pub show_window_count: bool,
+    /// Focus the switcher's monitor before switching workspaces on it.
+    pub focus_monitor_on_switch: bool,
+    /// What clicks on a workspace button do.
+    pub mouse_bindings: MouseBindings,
+    /// Look of the workspace buttons.
+    pub theme: Theme,
+    /// Label of the workspace buttons, see [`crate::label`].
+    pub label: LabelTemplate,
+    /// Names shown for workspaces by `{display_name}`, keyed by workspace name.
+    pub workspace_aliases: BTreeMap<String, String>,
+    #[serde(deserialize_with = "from_str")]
+    pub provider: ProviderKind,
+    /// Command printing snapshots for the external provider.
+    pub external_command: Option<String>,
+    /// Command template the external provider runs to activate a workspace.
+    pub external_activate_command: Option<String>,
+    /// Address of GlazeWM's IPC server.
+    pub glazewm_url: String,
+    /// Directory holding komorebi's socket, its data directory by default.
+    pub komorebi_data_dir: Option<PathBuf>,
+    /// Delay before the first reconnect attempt, doubling up to
+    /// `max_reconnect_delay_ms`.
+    pub reconnect_delay_ms: u64,
+    pub max_reconnect_delay_ms: u64,
+    /// Overrides for single monitors, keyed by their
+    /// [`id`](crate::state::Monitor::id).
+    pub monitors: BTreeMap<String, MonitorOptions>,
+}
+
+/// Options a single monitor can override, see [`Options::for_monitor`]. These
+/// are all the display options; the provider, its commands and the reconnect
+/// delays are shared by every monitor and rejected here.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MonitorOptions {
+    pub hide_empty_workspaces: Option<bool>,
+    pub hide_if_offline: Option<bool>,
+    pub enable_scroll_switching: Option<bool>,
+    pub show_window_count: Option<bool>,
+    pub focus_monitor_on_switch: Option<bool>,
+    /// Replaces the bindings of other monitors, applied over the defaults.
+    pub mouse_bindings: Option<MouseBindings>,
+    /// Replaces the theme of other monitors, applied over its preset.
+    pub theme: Option<Theme>,
+    pub label: Option<LabelTemplate>,
+    /// Added to the global aliases, replacing those of the same workspaces.
+    pub workspace_aliases: BTreeMap<String, String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            hide_empty_workspaces: true,
+            hide_if_offline: false,
+            enable_scroll_switching: true,
+            show_window_count: false,
+            focus_monitor_on_switch: false,
+            mouse_bindings: MouseBindings::default(),
+            theme: Theme::default(),
+            label: LabelTemplate::default(),
+            workspace_aliases: BTreeMap::new(),
+            provider: ProviderKind::Auto,
+            external_command: None,
+            external_activate_command: None,
+            glazewm_url: crate::glazewm::DEFAULT_URL.to_string(),
+            komorebi_data_dir: None,
+            reconnect_delay_ms: 500,
+            max_reconnect_delay_ms: 30_000,
+            monitors: BTreeMap::new(),
+        }
+    }
+}
+
+fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: std::str::FromStr<Err = anyhow::Error>,
+{
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(serde::de::Error::custom)
+}
+
+impl Options {
+    /// `config.toml` in the `glazewm-switcher` user config directory.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(
+            dirs::config_dir()?
+                .join("glazewm-switcher")
+                .join("config.toml"),
+        )
+    }
+
+    /// Reads the config file at `path`, using the defaults if it doesn't
+    /// exist, then applies the environment overrides.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut opts = match std::fs::read_to_string(path) {
+            // keep the cause in the message, it is shown in a dialog as is
+            Ok(text) => Self::parse(&text)
+                .map_err(|e| anyhow::anyhow!("Invalid config {}: {e:#}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read config {}", path.display()))
+            }
+        };
+
+        opts.apply_env();
+        Ok(opts)
+    }
+
+    /// Parses a config file. Unknown keys are rejected, missing ones keep
+    /// their default.
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// The delay between reconnect attempts.
+    pub fn backoff(&self) -> Backoff {
+        Backoff::new(
+            Duration::from_millis(self.reconnect_delay_ms),
+            Duration::from_millis(self.max_reconnect_delay_ms),
+        )
+    }
+
+    /// Names of the options that differ in `new` but only apply on restart,
+    /// since they pick the provider and how it connects.
+    pub fn restart_changes(&self, new: &Options) -> Vec<&'static str> {
+        let changes = [
+            ("provider", self.provider != new.provider),
+            (
+                "external_command",
+                self.external_command != new.external_command,
+            ),
+            (
+                "external_activate_command",
+                self.external_activate_command != new.external_activate_command,
+            ),
+            ("glazewm_url", self.glazewm_url != new.glazewm_url),
+            (
+                "komorebi_data_dir",
+                self.komorebi_data_dir != new.komorebi_data_dir,
+            ),
+            (
+                "reconnect_delay_ms",
+                self.reconnect_delay_ms != new.reconnect_delay_ms,
+            ),
+            (
+                "max_reconnect_delay_ms",
+                self.max_reconnect_delay_ms != new.max_reconnect_delay_ms,
+            ),
+        ];
+
+        changes
+            .into_iter()
+            .filter(|(_, changed)| *changed)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// The options of the monitor with `id`, with its overrides applied over
+    /// the global options and the environment.
+    pub fn for_monitor(&self, id: &str) -> Self {
+        let mut opts = self.clone();
+        let Some(o) = self.monitors.get(id) else {
+            return opts;
+        };
+
+        opts.hide_empty_workspaces = o
+            .hide_empty_workspaces
+            .unwrap_or(opts.hide_empty_workspaces);
+        opts.hide_if_offline = o.hide_if_offline.unwrap_or(opts.hide_if_offline);
+        opts.enable_scroll_switching = o
+            .enable_scroll_switching
+            .unwrap_or(opts.enable_scroll_switching);
+        opts.show_window_count = o.show_window_count.unwrap_or(opts.show_window_count);
+        opts.focus_monitor_on_switch = o
+            .focus_monitor_on_switch
+            .unwrap_or(opts.focus_monitor_on_switch);
+        if let Some(bindings) = &o.mouse_bindings {
+            opts.mouse_bindings = bindings.clone();
+        }
+        if let Some(theme) = &o.theme {
+            opts.theme = theme.clone();
+        }
+        if let Some(label) = &o.label {
+            opts.label = label.clone();
+        }
+        opts.workspace_aliases.extend(o.workspace_aliases.clone());
+
+        opts
+    }
+
+    fn apply_env(&mut self) {
+        if let Ok(val) = env::var("SWITCHER_HIDE_EMPTY_WORKSPACES") {
+            self.hide_empty_workspaces = matches!(val.as_str(), "1" | "true" | "yes");
+        }
+        if let Ok(val) = env::var("SWITCHER_HIDE_IF_OFFLINE") {
+            self.hide_if_offline = matches!(val.as_str(), "1" | "true" | "yes");
+        }
+        if let Ok(val) = env::var("SWITCHER_ENABLE_SCROLL_SWITCHING") {
+            self.enable_scroll_switching = matches!(val.as_str(), "1" | "true" | "yes");
+        }
+        if let Ok(val) = env::var("SWITCHER_SHOW_WINDOW_COUNT") {
+            self.show_window_count = matches!(val.as_str(), "1" | "true" | "yes");
+        }
+        if let Ok(val) = env::var("SWITCHER_FOCUS_MONITOR_ON_SWITCH") {
+            self.focus_monitor_on_switch = matches!(val.as_str(), "1" | "true" | "yes");
+        }
+        if let Ok(val) = env::var("SWITCHER_MOUSE_BINDINGS") {
+            let mut bindings = self.mouse_bindings.clone();
+            match bindings.apply(&val) {
+                Ok(()) => self.mouse_bindings = bindings,
+                Err(e) => tracing::warn!("Ignoring SWITCHER_MOUSE_BINDINGS: {e}"),
+            }
+        }
+        if let Ok(val) = env::var("SWITCHER_PROVIDER") {
+            match val.parse() {
+                Ok(provider) => self.provider = provider,
+                Err(e) => tracing::warn!("Ignoring SWITCHER_PROVIDER: {e}"),
+            }
+        }
+
+        if let Ok(val) = env::var("SWITCHER_EXTERNAL_COMMAND") {
+            self.external_command = Some(val).filter(|v| !v.trim().is_empty());
+        }
+        if let Ok(val) = env::var("SWITCHER_EXTERNAL_ACTIVATE_COMMAND") {
+            self.external_activate_command = Some(val).filter(|v| !v.trim().is_empty());
+        }
+    }
+}
+
+/// Polls the modification time of the config file at `path` every
+/// `interval`, calling `on_change` with the reloaded options whenever it
+/// changes. Stops once `on_change` returns `false`.
+pub fn watch(
+    path: PathBuf,
+    interval: Duration,
+    mut on_change: impl FnMut(anyhow::Result<Options>) -> bool + Send + 'static,
+) -> JoinHandle<()> {
+    let modified = |path: &Path| -> Option<SystemTime> { path.metadata().ok()?.modified().ok() };
+
+    std::thread::spawn(move || {
+        let mut last = modified(&path);
+        loop {
+            std::thread::sleep(interval);
+
+            let current = modified(&path);
+            if current == last {
+                continue;
+            }
+            last = current;
+
+            tracing::info!("Reloading config {}", path.display());
+            if !on_change(Options::load(&path)) {
+                return;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::bindings::MouseTrigger;
+    use crate::provider::WorkspaceAction;
+
+    #[test]
+    fn parses_config_over_defaults() {
+        let opts = Options::parse(
+            r#"
+            hide_empty_workspaces = false
+            provider = "komorebi"
+            reconnect_delay_ms = 100
+            label = "{index}[ {window_count}]"
+
+            [workspace_aliases]
+            web = "🌐"
+
+            [mouse_bindings]
+            middle = "close-windows"
+            "shift+left" = "none"
+            "#,
+        )
+        .unwrap();
+
+        assert!(!opts.hide_empty_workspaces);
+        assert!(opts.enable_scroll_switching);
+        assert_eq!(opts.provider, ProviderKind::Komorebi);
+        assert_eq!(opts.backoff().next_delay(), Duration::from_millis(100));
+        assert_eq!(opts.label.to_string(), "{index}[ {window_count}]");
+        assert_eq!(opts.workspace_aliases["web"], "🌐");
+
+        let action = |s: &str| {
+            opts.mouse_bindings
+                .action(s.parse::<MouseTrigger>().unwrap())
+        };
+        assert_eq!(action("left"), Some(WorkspaceAction::Focus));
+        assert_eq!(action("middle"), Some(WorkspaceAction::CloseAllWindows));
+        assert_eq!(action("shift+left"), None);
+    }
+
+    #[test]
+    fn rejects_unknown_and_invalid_keys() {
+        let error = |text: &str| format!("{:#}", Options::parse(text).unwrap_err());
+
+        let unknown = error("hide_empty_workspace = true");
+        assert!(
+            unknown.contains("unknown field `hide_empty_workspace`"),
+            "{unknown}"
+        );
+
+        let provider = error(r#"provider = "i3""#);
+        assert!(provider.contains("Unknown provider `i3`"), "{provider}");
+        assert!(provider.contains("line 1"), "{provider}");
+
+        let binding = error("[mouse_bindings]\nright = \"focus\"");
+        assert!(
+            binding.contains("Invalid mouse binding `right`"),
+            "{binding}"
+        );
+
+        let kind = error("show_window_count = \"yes\"");
+        assert!(kind.contains("expected a boolean"), "{kind}");
+    }
+
+    #[test]
+    fn overrides_options_per_monitor() {
+        let opts = Options::parse(
+            r#"
+            hide_empty_workspaces = true
+            show_window_count = true
+
+            [workspace_aliases]
+            web = "🌐"
+            code = "💻"
+
+            [monitors.laptop]
+            hide_empty_workspaces = false
+
+            [monitors.laptop.workspace_aliases]
+            web = "w"
+
+            [monitors."DEL40F6-5&2a8e1f&0&UID4353"]
+            enable_scroll_switching = false
+
+            [monitors."DEL40F6-5&2a8e1f&0&UID4353".mouse_bindings]
+            middle = "close-windows"
+            "#,
+        )
+        .unwrap();
+
+        let laptop = opts.for_monitor("laptop");
+        assert!(!laptop.hide_empty_workspaces);
+        assert!(laptop.show_window_count);
+        assert!(laptop.enable_scroll_switching);
+        assert_eq!(laptop.workspace_aliases["web"], "w");
+        assert_eq!(laptop.workspace_aliases["code"], "💻");
+
+        let ultrawide = opts.for_monitor("DEL40F6-5&2a8e1f&0&UID4353");
+        assert!(ultrawide.hide_empty_workspaces);
+        assert!(!ultrawide.enable_scroll_switching);
+        let middle = "middle".parse::<MouseTrigger>().unwrap();
+        assert_eq!(
+            ultrawide.mouse_bindings.action(middle),
+            Some(WorkspaceAction::CloseAllWindows)
+        );
+        assert_eq!(
+            opts.for_monitor("other").mouse_bindings.action(middle),
+            Some(WorkspaceAction::MoveFocusedWindowHere)
+        );
+
+        assert_eq!(opts.for_monitor("other").workspace_aliases["web"], "🌐");
+    }
+
+    #[test]
+    fn rejects_global_options_per_monitor() {
+        let error = format!(
+            "{:#}",
+            Options::parse("[monitors.laptop]\nprovider = \"komorebi\"").unwrap_err()
+        );
+        assert!(error.contains("unknown field `provider`"), "{error}");
+        assert!(error.contains("`workspace_aliases`"), "{error}");
+        assert!(error.contains("line 2"), "{error}");
+    }
+
+    #[test]
+    fn lists_changes_needing_a_restart() {
+        let old = Options::default();
+        let new = Options::parse(
+            r#"
+            show_window_count = true
+            provider = "komorebi"
+            reconnect_delay_ms = 100
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            old.restart_changes(&new),
+            ["provider", "reconnect_delay_ms"]
+        );
+        assert!(old.restart_changes(&old.clone()).is_empty());
+    }
+
+    #[test]
+    fn watch_reloads_changed_config() {
+        let dir = std::env::temp_dir().join(format!("switcher-options-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "show_window_count = false").unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let watcher = watch(path.clone(), Duration::from_millis(10), move |opts| {
+            tx.send(opts.map(|o| o.show_window_count).map_err(|e| e.to_string()))
+                .is_ok()
+        });
+
+        // make sure the modification time changes on coarse file systems
+        std::thread::sleep(Duration::from_millis(50));
+        std::fs::write(&path, "show_window_count = true").unwrap();
+        let file_time = SystemTime::now() + Duration::from_secs(1);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(file_time)
+            .unwrap();
+
+        let reloaded = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(reloaded, Ok(true));
+
+        drop(rx);
+        std::fs::write(&path, "show_window_count = 1").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(file_time + Duration::from_secs(1))
+            .unwrap();
+        watcher.join().unwrap();
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,98 @@
+//! A scripted provider for tests.
+
+use std::sync::{Mutex, PoisonError};
+
+use super::WorkspaceProvider;
+use crate::state::State;
+
+/// A call made on a [`MockProvider`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Call {
+    Activate {
+        monitor_idx: usize,
+        workspace: String,
+        focus_monitor: bool,
+    },
+    Command(String),
+}
+
+/// Publishes a fixed list of states and records every call made on it.
+pub struct MockProvider {
+    states: Vec<State>,
+    failures: Mutex<usize>,
+    calls: Mutex<Vec<Call>>,
+}
+
+impl MockProvider {
+    pub fn new(states: Vec<State>) -> Self {
+        Self {
+            states,
+            failures: Mutex::new(0),
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Makes the first `failures` calls to `watch` fail to connect.
+    pub fn with_failures(self, failures: usize) -> Self {
+        *self.failures.lock().unwrap_or_else(PoisonError::into_inner) = failures;
+        self
+    }
+
+    pub fn calls(&self) -> Vec<Call> {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn record(&self, call: Call) {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(call);
+    }
+}
+
+impl WorkspaceProvider for MockProvider {
+    fn name(&self) -> &str {
+        "Mock"
+    }
+
+    fn snapshot(&self) -> anyhow::Result<State> {
+        Ok(self.states.last().cloned().unwrap_or_default())
+    }
+
+    fn watch(&self, sink: &mut dyn FnMut(State)) -> anyhow::Result<()> {
+        let mut failures = self.failures.lock().unwrap_or_else(PoisonError::into_inner);
+        if *failures > 0 {
+            *failures -= 1;
+            anyhow::bail!("Mock is not running");
+        }
+        drop(failures);
+
+        for state in &self.states {
+            sink(state.clone());
+        }
+
+        Ok(())
+    }
+
+    fn activate_workspace(
+        &self,
+        monitor_idx: usize,
+        workspace_name: &str,
+        focus_monitor: bool,
+    ) -> anyhow::Result<()> {
+        self.record(Call::Activate {
+            monitor_idx,
+            workspace: workspace_name.into(),
+            focus_monitor,
+        });
+        Ok(())
+    }
+
+    fn run_command(&self, command: &str) -> anyhow::Result<()> {
+        self.record(Call::Command(command.into()));
+        Ok(())
+    }
+}
@@ -0,0 +1,441 @@
+//! Window manager backends the switcher can display and control.
+
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use anyhow::Context;
+
+use crate::connection::{Backoff, ConnectionState, ConnectionTracker};
+use crate::external::External;
+use crate::glazewm::GlazeWm;
+use crate::komorebi::Komorebi;
+use crate::options::Options;
+use crate::state::State;
+
+#[cfg(test)]
+pub mod mock;
+
+/// Something a provider publishes while it runs.
+#[derive(Clone, Debug)]
+pub enum ProviderEvent {
+    State(State),
+    Connection(ConnectionState),
+}
+
+/// Receives every event a provider publishes.
+pub type EventSink = Box<dyn FnMut(ProviderEvent) + Send>;
+
+/// A window manager command offered in the switcher context menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MenuCommand {
+    pub label: &'static str,
+    /// Passed to [`WorkspaceProvider::run_command`].
+    pub command: &'static str,
+}
+
+/// Something the context menu of a single workspace can do with it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkspaceAction {
+    Focus,
+    /// Moves the focused window to the workspace.
+    MoveFocusedWindowHere,
+    /// Moves the focused window to the workspace and focuses the workspace.
+    MoveFocusedWindowAndFollow,
+    MoveToNextMonitor,
+    MoveToPreviousMonitor,
+    CloseAllWindows,
+}
+
+impl WorkspaceAction {
+    const ALL: [Self; 6] = [
+        Self::Focus,
+        Self::MoveFocusedWindowHere,
+        Self::MoveFocusedWindowAndFollow,
+        Self::MoveToNextMonitor,
+        Self::MoveToPreviousMonitor,
+        Self::CloseAllWindows,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Focus => "Focus",
+            Self::MoveFocusedWindowHere => "Move focused window here",
+            Self::MoveFocusedWindowAndFollow => "Move focused window here and follow",
+            Self::MoveToNextMonitor => "Move to next monitor",
+            Self::MoveToPreviousMonitor => "Move to previous monitor",
+            Self::CloseAllWindows => "Close all windows",
+        }
+    }
+
+    /// Name used in the options, e.g. `move-window-here`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Focus => "focus",
+            Self::MoveFocusedWindowHere => "move-window-here",
+            Self::MoveFocusedWindowAndFollow => "move-window-and-follow",
+            Self::MoveToNextMonitor => "move-to-next-monitor",
+            Self::MoveToPreviousMonitor => "move-to-previous-monitor",
+            Self::CloseAllWindows => "close-windows",
+        }
+    }
+}
+
+impl FromStr for WorkspaceAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.name().eq_ignore_ascii_case(s))
+            .with_context(|| {
+                let names = Self::ALL.map(|a| format!("`{}`", a.name())).join(", ");
+                format!("Unknown workspace action `{s}`, expected one of {names}")
+            })
+    }
+}
+
+/// A window manager backend.
+pub trait WorkspaceProvider: Send + Sync {
+    /// Human readable name of the window manager, e.g. `GlazeWM`.
+    fn name(&self) -> &str;
+
+    /// Reads the current state once.
+    fn snapshot(&self) -> anyhow::Result<State>;
+
+    /// Follows state changes, calling `sink` with each new state, until the
+    /// connection is lost. Returns `Ok` once there is nothing more to publish.
+    fn watch(&self, sink: &mut dyn FnMut(State)) -> anyhow::Result<()>;
+
+    /// Activates the workspace named `workspace_name` on the monitor at
+    /// `monitor_idx`, focusing that monitor first when `focus_monitor` is set.
+    fn activate_workspace(
+        &self,
+        monitor_idx: usize,
+        workspace_name: &str,
+        focus_monitor: bool,
+    ) -> anyhow::Result<()>;
+
+    /// Actions to offer in the context menu of a workspace.
+    fn workspace_actions(&self) -> &[WorkspaceAction] {
+        &[WorkspaceAction::Focus]
+    }
+
+    /// Runs `action` on the workspace named `workspace_name` on the monitor
+    /// at `monitor_idx`. [`WorkspaceAction::Focus`] focuses the monitor first
+    /// when `focus_monitor` is set, like [`WorkspaceProvider::activate_workspace`].
+    fn run_workspace_action(
+        &self,
+        monitor_idx: usize,
+        workspace_name: &str,
+        action: WorkspaceAction,
+        focus_monitor: bool,
+    ) -> anyhow::Result<()> {
+        match action {
+            WorkspaceAction::Focus => {
+                self.activate_workspace(monitor_idx, workspace_name, focus_monitor)
+            }
+            _ => anyhow::bail!("{} doesn't support {:?}", self.name(), action.label()),
+        }
+    }
+
+    /// Moves the workspace named `workspace_name` from the monitor at
+    /// `monitor_idx` to the one at `target_monitor_idx`.
+    fn move_workspace_to_monitor(
+        &self,
+        monitor_idx: usize,
+        workspace_name: &str,
+        target_monitor_idx: usize,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!(
+            "{} can't move workspace {workspace_name:?} from monitor {monitor_idx} to {target_monitor_idx}",
+            self.name()
+        )
+    }
+
+    /// Leaves the binding mode named `name`.
+    fn disable_binding_mode(&self, name: &str) -> anyhow::Result<()> {
+        anyhow::bail!("{} has no binding modes to disable {name:?}", self.name())
+    }
+
+    /// Whether [`WorkspaceProvider::toggle_pause`] is supported, so menus
+    /// only offer pausing when it can work.
+    fn supports_pause(&self) -> bool {
+        false
+    }
+
+    /// Pauses the window manager, or resumes it when paused.
+    fn toggle_pause(&self) -> anyhow::Result<()> {
+        anyhow::bail!("{} can't be paused", self.name())
+    }
+
+    /// Switches the tiling direction of the workspace named `workspace_name`
+    /// on the monitor at `monitor_idx`, whether it is focused or not.
+    fn toggle_tiling_direction(
+        &self,
+        monitor_idx: usize,
+        workspace_name: &str,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!(
+            "{} can't toggle the tiling direction of workspace {workspace_name:?} on monitor {monitor_idx}",
+            self.name()
+        )
+    }
+
+    /// Runs a raw window manager command.
+    fn run_command(&self, command: &str) -> anyhow::Result<()>;
+
+    /// Common commands to offer in the context menu.
+    fn menu_commands(&self) -> &[MenuCommand] {
+        &[]
+    }
+}
+
+/// Which window manager backend to use.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProviderKind {
+    /// The external command if one is configured, otherwise whichever window
+    /// manager is running, GlazeWM if none is.
+    #[default]
+    Auto,
+    GlazeWm,
+    Komorebi,
+    External,
+}
+
+impl FromStr for ProviderKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "glazewm" => Ok(Self::GlazeWm),
+            "komorebi" => Ok(Self::Komorebi),
+            "external" => Ok(Self::External),
+            _ => anyhow::bail!(
+                "Unknown provider `{s}`, expected `auto`, `glazewm`, `komorebi` or `external`"
+            ),
+        }
+    }
+}
+
+/// Creates the backend picked by `options`, detecting the running window
+/// manager for [`ProviderKind::Auto`].
+pub fn create(options: &Options) -> anyhow::Result<Arc<dyn WorkspaceProvider>> {
+    let external = || {
+        let command = options
+            .external_command
+            .clone()
+            .context(
+                "Set `external_command` in the config file or SWITCHER_EXTERNAL_COMMAND to use the external provider",
+            )?;
+        let activate_command = options.external_activate_command.clone();
+        anyhow::Ok(Arc::new(External::new(command, activate_command)))
+    };
+    let glazewm = || GlazeWm::new(options.glazewm_url.clone());
+    let komorebi = || Komorebi::new(options.komorebi_data_dir.clone());
+
+    let provider: Arc<dyn WorkspaceProvider> = match options.provider {
+        ProviderKind::GlazeWm => Arc::new(glazewm()),
+        ProviderKind::Komorebi => Arc::new(komorebi()),
+        ProviderKind::External => external()?,
+        ProviderKind::Auto if options.external_command.is_some() => external()?,
+        ProviderKind::Auto => {
+            let (glazewm, komorebi) = (glazewm(), komorebi());
+            if !glazewm.is_running() && komorebi.is_running() {
+                Arc::new(komorebi)
+            } else {
+                Arc::new(glazewm)
+            }
+        }
+    };
+
+    tracing::info!("Using the {} provider", provider.name());
+
+    Ok(provider)
+}
+
+/// Runs [`WorkspaceProvider::watch`] on a background thread, reconnecting with
+/// `backoff` and reporting the connection state to `sink`.
+pub fn spawn_subscription(
+    provider: Arc<dyn WorkspaceProvider>,
+    backoff: Backoff,
+    sink: EventSink,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || supervise(&*provider, sink, backoff))
+}
+
+fn supervise(provider: &dyn WorkspaceProvider, mut sink: EventSink, mut backoff: Backoff) {
+    let mut tracker = ConnectionTracker::default();
+    sink(ProviderEvent::Connection(tracker.state()));
+
+    loop {
+        let result = provider.watch(&mut |state| {
+            if let Some(connection) = tracker.connected() {
+                tracing::info!("Connected to {}", provider.name());
+                backoff.reset();
+                sink(ProviderEvent::Connection(connection));
+            }
+            sink(ProviderEvent::State(state));
+        });
+
+        let Err(e) = result else {
+            return;
+        };
+
+        let delay = backoff.next_delay();
+        match tracker.failed() {
+            Some(connection) => {
+                tracing::warn!("{} connection failed: {e}", provider.name());
+                sink(ProviderEvent::Connection(connection));
+            }
+            None => tracing::debug!("{} connection failed: {e}", provider.name()),
+        }
+
+        tracing::debug!("Retrying {} in {delay:?}", provider.name());
+        std::thread::sleep(delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use super::mock::{Call, MockProvider};
+    use super::*;
+    use crate::state::{Monitor, Workspace};
+
+    fn state(focused: &str) -> State {
+        let workspace = |name: &str| Workspace {
+            name: name.into(),
+            focused: name == focused,
+            ..Default::default()
+        };
+
+        State {
+            monitors: vec![Monitor {
+                name: "Display 1".into(),
+                id: "DISPLAY1".into(),
+                workspaces: vec![workspace("1"), workspace("2")],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_provider_kind() {
+        assert_eq!(
+            "GlazeWM".parse::<ProviderKind>().unwrap(),
+            ProviderKind::GlazeWm
+        );
+        assert_eq!(
+            "komorebi".parse::<ProviderKind>().unwrap(),
+            ProviderKind::Komorebi
+        );
+        assert!("i3".parse::<ProviderKind>().is_err());
+    }
+
+    #[test]
+    fn external_provider_needs_a_command() {
+        let options = Options {
+            provider: ProviderKind::External,
+            ..Default::default()
+        };
+        let error = create(&options).err().unwrap().to_string();
+        assert!(error.contains("`external_command`"), "{error}");
+        assert!(error.contains("SWITCHER_EXTERNAL_COMMAND"), "{error}");
+    }
+
+    fn focused(state: &State) -> String {
+        let monitor = &state.monitors[0];
+        let workspace = monitor.workspaces.iter().find(|w| w.focused).unwrap();
+        workspace.name.clone()
+    }
+
+    /// Runs the subscription of `provider` to its end, describing each event.
+    fn events(provider: MockProvider) -> Vec<String> {
+        let (tx, rx) = mpsc::channel();
+        let sink: EventSink = Box::new(move |event| tx.send(event).unwrap());
+        let backoff = Backoff::new(Duration::from_millis(1), Duration::from_millis(1));
+        supervise(&provider, sink, backoff);
+
+        rx.iter()
+            .map(|event| match event {
+                ProviderEvent::State(state) => focused(&state),
+                ProviderEvent::Connection(connection) => format!("{connection:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn subscription_forwards_states_to_sink() {
+        let provider = MockProvider::new(vec![state("1"), state("2")]);
+        assert_eq!(events(provider), ["Connecting", "Connected", "1", "2"]);
+    }
+
+    #[test]
+    fn subscription_reports_offline_until_connected() {
+        let provider = MockProvider::new(vec![state("1")]).with_failures(4);
+        assert_eq!(
+            events(provider),
+            ["Connecting", "Offline", "Connected", "1"]
+        );
+    }
+
+    #[test]
+    fn records_activations_and_commands() {
+        let mock = Arc::new(MockProvider::new(vec![state("1")]));
+        let provider: Arc<dyn WorkspaceProvider> = mock.clone();
+
+        let state = provider.snapshot().unwrap();
+        let monitor = &state.monitors[0];
+        let target = monitor.scroll_target(-1.0).unwrap();
+        provider
+            .activate_workspace(monitor.index, &target.name, true)
+            .unwrap();
+        provider.run_command("wm-redraw").unwrap();
+
+        assert_eq!(
+            mock.calls(),
+            [
+                Call::Activate {
+                    monitor_idx: 0,
+                    workspace: "2".into(),
+                    focus_monitor: true,
+                },
+                Call::Command("wm-redraw".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn pausing_is_unsupported_by_default() {
+        let mock = MockProvider::new(vec![state("1")]);
+
+        assert!(!mock.supports_pause());
+        assert!(mock.toggle_pause().is_err());
+    }
+
+    #[test]
+    fn workspace_actions_default_to_focus() {
+        let mock = MockProvider::new(vec![state("1")]);
+
+        assert_eq!(mock.workspace_actions(), [WorkspaceAction::Focus]);
+        mock.run_workspace_action(0, "2", WorkspaceAction::Focus, false)
+            .unwrap();
+        assert!(mock
+            .run_workspace_action(0, "2", WorkspaceAction::CloseAllWindows, false)
+            .is_err());
+
+        assert_eq!(
+            mock.calls(),
+            [Call::Activate {
+                monitor_idx: 0,
+                workspace: "2".into(),
+                focus_monitor: false,
+            }]
+        );
+    }
+}
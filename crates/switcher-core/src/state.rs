@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Workspace {
+    pub name: String,
+    #[serde(default)]
+    pub focused: bool,
+    #[serde(default)]
+    pub is_empty: bool,
+    /// Windows on the workspace, including those nested in splits. Zero when
+    /// the window manager doesn't report it.
+    #[serde(default)]
+    pub window_count: usize,
+    /// Details of the windows on the workspace, empty when the window manager
+    /// doesn't report them.
+    #[serde(default)]
+    pub windows: Vec<Window>,
+    /// Direction new windows are tiled in, `None` when the window manager
+    /// doesn't report it.
+    #[serde(default)]
+    pub tiling_direction: Option<TilingDirection>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TilingDirection {
+    Horizontal,
+    Vertical,
+}
+
+/// A window managed on a workspace.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Window {
+    /// Native window handle.
+    pub handle: isize,
+    pub title: String,
+    /// Executable name without extension, e.g. `firefox`.
+    pub process_name: String,
+    pub class_name: String,
+    pub state: WindowState,
+    /// Whether this window was focused last on its workspace, so it gets focus
+    /// when the workspace is shown.
+    pub focused: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowState {
+    #[default]
+    Tiling,
+    Floating,
+    Minimized,
+    Fullscreen,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Monitor {
+    pub name: String,
+    /// Position in [`State::monitors`].
+    #[serde(skip)]
+    pub index: usize,
+    pub id: String,
+    pub workspaces: Vec<Workspace>,
+    pub rect: Rect,
+    /// Zero when the window manager doesn't report it.
+    pub dpi: u32,
+}
+
+/// A screen rectangle in physical pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl Rect {
+    pub fn contains(&self, other: &Rect) -> bool {
+        self.left <= other.left
+            && self.top <= other.top
+            && self.right >= other.right
+            && self.bottom >= other.bottom
+    }
+
+    pub fn contains_point(&self, x: i32, y: i32) -> bool {
+        (self.left..self.right).contains(&x) && (self.top..self.bottom).contains(&y)
+    }
+}
+
+impl Monitor {
+    /// The workspace a scroll by `delta` switches to: the previous one when
+    /// scrolling up, the next one when scrolling down, wrapping around.
+    pub fn scroll_target(&self, delta: f32) -> Option<&Workspace> {
+        let count = self.workspaces.len();
+        if count == 0 || delta == 0.0 {
+            return None;
+        }
+
+        let focused_idx = self.workspaces.iter().position(|w| w.focused).unwrap_or(0);
+        let next_idx = if delta > 0.0 {
+            focused_idx.checked_sub(1).unwrap_or(count - 1)
+        } else {
+            (focused_idx + 1) % count
+        };
+
+        self.workspaces.get(next_idx)
+    }
+}
+
+/// A readable monitor name, e.g. `Display 1` for `\\.\DISPLAY1` or `DISPLAY1`.
+pub fn display_name(device_name: &str) -> String {
+    let name = device_name.trim_start_matches(['\\', '.']);
+    match name.strip_prefix("DISPLAY") {
+        Some(number) if number.parse::<u32>().is_ok() => format!("Display {number}"),
+        _ => name.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct State {
+    pub monitors: Vec<Monitor>,
+    /// Binding modes currently active, e.g. GlazeWM's `resize` mode.
+    #[serde(default)]
+    pub binding_modes: Vec<BindingMode>,
+    /// Whether the window manager is paused and ignores its keybindings.
+    #[serde(default)]
+    pub paused: bool,
+}
+
+/// A window manager mode that changes what keybindings do.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BindingMode {
+    pub name: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+impl BindingMode {
+    /// Name to show for this mode.
+    pub fn label(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+}
+
+impl State {
+    /// Parses plain text listing one workspace name per line, where a `*` or
+    /// `>` prefix marks the focused one, as a single monitor with id `monitor_id`.
+    pub fn from_plain_text(text: &str, monitor_id: &str) -> anyhow::Result<Self> {
+        // Parse non-JSON output: assume each non-empty trimmed line is a workspace name.
+        // Detect focus markers like "*" or ">" prefix.
+        let mut names = Vec::new();
+        let mut focused_idx: Option<usize> = None;
+        for raw in text.lines() {
+            let line = raw.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut name = line.to_string();
+            if let Some(stripped) = name.strip_prefix("*") {
+                // e.g., "*1" or "* Workspace 1"
+                focused_idx = Some(names.len());
+                name = stripped.trim().to_string();
+            } else if let Some(stripped) = name.strip_prefix(">") {
+                // e.g., "> 1"
+                focused_idx = Some(names.len());
+                name = stripped.trim().to_string();
+            }
+            names.push(name);
+        }
+
+        if names.is_empty() {
+            anyhow::bail!("no workspaces in plain text")
+        }
+
+        let k_workspaces = names
+            .into_iter()
+            .enumerate()
+            .map(|(idx, name)| Workspace {
+                name,
+                focused: focused_idx.map(|f| f == idx).unwrap_or(idx == 0),
+                ..Default::default()
+            })
+            .collect();
+
+        let monitor = Monitor {
+            name: "Monitor".into(),
+            index: 0,
+            id: monitor_id.into(),
+            workspaces: k_workspaces,
+            rect: Rect::default(),
+            dpi: 0,
+        };
+
+        Ok(Self {
+            monitors: vec![monitor],
+            ..Default::default()
+        })
+    }
+
+    /// Parses a JSON snapshot in the schema documented in the README, where
+    /// everything but workspace names is optional.
+    pub fn from_json(text: &str) -> anyhow::Result<Self> {
+        let de = &mut serde_json::Deserializer::from_str(text);
+        let mut state: Self = serde_path_to_error::deserialize(de).map_err(|e| {
+            let path = e.path().to_string();
+            anyhow::anyhow!("Invalid state at `{path}`: {}", e.into_inner())
+        })?;
+
+        for (index, monitor) in state.monitors.iter_mut().enumerate() {
+            monitor.index = index;
+            if monitor.id.is_empty() {
+                monitor.id = monitor.name.clone();
+            }
+        }
+
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scroll_target_wraps_around() {
+        let monitor = Monitor {
+            workspaces: ["1", "web", "3"]
+                .into_iter()
+                .map(|name| Workspace {
+                    name: name.into(),
+                    focused: name == "1",
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(monitor.scroll_target(1.0).unwrap().name, "3");
+        assert_eq!(monitor.scroll_target(-1.0).unwrap().name, "web");
+        assert!(monitor.scroll_target(0.0).is_none());
+    }
+
+    #[test]
+    fn parses_json_snapshot() {
+        let state = State::from_json(
+            r#"{"monitors": [
+                {"name": "left", "workspaces": [{"name": "1", "focused": true}, {"name": "web"}]},
+                {"name": "right", "id": "DEL40F6", "rect": {"left": 1920, "right": 3840, "bottom": 1080}, "workspaces": []}
+            ]}"#,
+        )
+        .unwrap();
+
+        let left = &state.monitors[0];
+        assert_eq!((left.index, left.id.as_str()), (0, "left"));
+        assert!(left.workspaces[0].focused && !left.workspaces[1].focused);
+
+        let right = &state.monitors[1];
+        assert_eq!((right.index, right.id.as_str()), (1, "DEL40F6"));
+        assert_eq!((right.rect.left, right.rect.right), (1920, 3840));
+
+        let error = State::from_json(r#"{"monitors": [{"workspaces": [{}]}]}"#).unwrap_err();
+        assert!(
+            error.to_string().contains("monitors[0].workspaces[0]"),
+            "{error}"
+        );
+    }
+}
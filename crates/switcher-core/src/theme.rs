@@ -0,0 +1,338 @@
+//! Look of the workspace buttons, picked from a preset and adjusted in the
+//! `[theme]` table of the config file.
+
+use std::str::FromStr;
+
+use egui::{vec2, Color32, Vec2};
+use serde::{Deserialize, Deserializer};
+
+/// Sizes and colors of the workspace buttons, in points.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "ThemeConfig")]
+pub struct Theme {
+    pub radius: f32,
+    pub min_size: Vec2,
+    /// Width of the indicator line of the focused workspace.
+    pub indicator_focused_width: f32,
+    pub indicator_width: f32,
+    pub indicator_height: f32,
+    /// Space around the label, split evenly between both sides.
+    pub padding: Vec2,
+    pub colors: ThemeColors,
+}
+
+/// Colors of a [`Theme`], falling back to the [`SystemColors`] when unset.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeColors {
+    #[serde(deserialize_with = "hex_color")]
+    pub text: Option<Color32>,
+    /// Background of a hovered button.
+    #[serde(deserialize_with = "hex_color")]
+    pub hover: Option<Color32>,
+    /// Background of the focused workspace button.
+    #[serde(deserialize_with = "hex_color")]
+    pub background: Option<Color32>,
+    #[serde(deserialize_with = "hex_color")]
+    pub stroke: Option<Color32>,
+    /// Indicator of the focused workspace, the system accent color by default.
+    #[serde(deserialize_with = "hex_color")]
+    pub indicator: Option<Color32>,
+    /// Indicator of the other workspaces holding windows.
+    #[serde(deserialize_with = "hex_color")]
+    pub inactive_indicator: Option<Color32>,
+}
+
+impl ThemeColors {
+    /// These colors, with the unset ones taken from `other`.
+    fn or(self, other: Self) -> Self {
+        Self {
+            text: self.text.or(other.text),
+            hover: self.hover.or(other.hover),
+            background: self.background.or(other.background),
+            stroke: self.stroke.or(other.stroke),
+            indicator: self.indicator.or(other.indicator),
+            inactive_indicator: self.inactive_indicator.or(other.inactive_indicator),
+        }
+    }
+}
+
+fn hex_color<'de, D>(deserializer: D) -> Result<Option<Color32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    Color32::from_hex(&s).map(Some).map_err(|_| {
+        serde::de::Error::custom(format!(
+            "Invalid color `{s}`, expected `#rrggbb` or `#rrggbbaa`"
+        ))
+    })
+}
+
+/// Colors of the system a [`Theme`] falls back to.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SystemColors {
+    pub dark_mode: bool,
+    pub accent: Option<Color32>,
+    pub foreground: Option<Color32>,
+}
+
+/// The colors a button is painted with, see [`Theme::palette`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Palette {
+    pub text: Color32,
+    pub hover: Color32,
+    pub background: Color32,
+    pub stroke: Color32,
+    pub indicator: Color32,
+    pub inactive_indicator: Color32,
+}
+
+impl Theme {
+    /// Resolves the colors of the theme, using `system` for the unset ones.
+    pub fn palette(&self, system: &SystemColors) -> Palette {
+        let colors = &self.colors;
+        let (background, stroke, inactive_indicator, text) = if system.dark_mode {
+            (
+                Color32::from_rgba_unmultiplied(255, 255, 255, 1),
+                Color32::from_rgba_unmultiplied(255, 255, 255, 2),
+                Color32::from_rgba_unmultiplied(180, 173, 170, 125),
+                Color32::WHITE,
+            )
+        } else {
+            (
+                Color32::from_rgba_unmultiplied(255, 255, 255, 30),
+                Color32::from_rgba_unmultiplied(33, 33, 33, 33),
+                Color32::from_rgba_unmultiplied(31, 31, 31, 150),
+                Color32::BLACK,
+            )
+        };
+
+        Palette {
+            text: colors.text.or(system.foreground).unwrap_or(text),
+            hover: colors.hover.unwrap_or(background),
+            background: colors.background.unwrap_or(background),
+            stroke: colors.stroke.unwrap_or(stroke),
+            indicator: colors.indicator.or(system.accent).unwrap_or(Color32::CYAN),
+            inactive_indicator: colors.inactive_indicator.unwrap_or(inactive_indicator),
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        ThemePreset::default().theme()
+    }
+}
+
+/// Named starting points of a [`Theme`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum ThemePreset {
+    /// The look of the Windows 11 taskbar.
+    #[default]
+    Windows11,
+    /// Smaller buttons without backgrounds.
+    Minimal,
+    /// Square buttons with opaque backgrounds, white text and outlines.
+    HighContrast,
+}
+
+impl ThemePreset {
+    pub const ALL: [Self; 3] = [Self::Windows11, Self::Minimal, Self::HighContrast];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Windows11 => "windows-11",
+            Self::Minimal => "minimal",
+            Self::HighContrast => "high-contrast",
+        }
+    }
+
+    pub fn theme(self) -> Theme {
+        match self {
+            Self::Windows11 => Theme {
+                radius: 4.0,
+                min_size: vec2(28.0, 28.0),
+                indicator_focused_width: 14.0,
+                indicator_width: 6.0,
+                indicator_height: 3.5,
+                padding: vec2(16.0, 8.0),
+                colors: ThemeColors::default(),
+            },
+            Self::Minimal => Theme {
+                radius: 2.0,
+                min_size: vec2(24.0, 24.0),
+                indicator_focused_width: 10.0,
+                indicator_width: 4.0,
+                indicator_height: 2.0,
+                padding: vec2(12.0, 6.0),
+                colors: ThemeColors {
+                    hover: Some(Color32::TRANSPARENT),
+                    background: Some(Color32::TRANSPARENT),
+                    stroke: Some(Color32::TRANSPARENT),
+                    ..Default::default()
+                },
+            },
+            Self::HighContrast => Theme {
+                radius: 0.0,
+                min_size: vec2(28.0, 28.0),
+                indicator_focused_width: 20.0,
+                indicator_width: 8.0,
+                indicator_height: 4.0,
+                padding: vec2(16.0, 8.0),
+                colors: ThemeColors {
+                    text: Some(Color32::WHITE),
+                    hover: Some(Color32::from_gray(48)),
+                    background: Some(Color32::BLACK),
+                    stroke: Some(Color32::WHITE),
+                    inactive_indicator: Some(Color32::from_gray(200)),
+                    ..Default::default()
+                },
+            },
+        }
+    }
+}
+
+impl FromStr for ThemePreset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase().replace([' ', '_'], "-");
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.name() == name)
+            .ok_or_else(|| {
+                let names = Self::ALL.map(|p| format!("`{}`", p.name())).join(", ");
+                anyhow::anyhow!("Unknown theme preset `{s}`, expected one of {names}")
+            })
+    }
+}
+
+impl TryFrom<String> for ThemePreset {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// The `[theme]` table: a preset and the values changed from it.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeConfig {
+    preset: ThemePreset,
+    radius: Option<f32>,
+    min_size: Option<[f32; 2]>,
+    indicator_focused_width: Option<f32>,
+    indicator_width: Option<f32>,
+    indicator_height: Option<f32>,
+    padding: Option<[f32; 2]>,
+    colors: ThemeColors,
+}
+
+impl TryFrom<ThemeConfig> for Theme {
+    type Error = anyhow::Error;
+
+    fn try_from(config: ThemeConfig) -> Result<Self, Self::Error> {
+        let preset = config.preset.theme();
+        let theme = Self {
+            radius: config.radius.unwrap_or(preset.radius),
+            min_size: config.min_size.map_or(preset.min_size, Vec2::from),
+            indicator_focused_width: config
+                .indicator_focused_width
+                .unwrap_or(preset.indicator_focused_width),
+            indicator_width: config.indicator_width.unwrap_or(preset.indicator_width),
+            indicator_height: config.indicator_height.unwrap_or(preset.indicator_height),
+            padding: config.padding.map_or(preset.padding, Vec2::from),
+            colors: config.colors.or(preset.colors),
+        };
+
+        let sizes = [
+            theme.radius,
+            theme.min_size.x,
+            theme.min_size.y,
+            theme.indicator_focused_width,
+            theme.indicator_width,
+            theme.indicator_height,
+            theme.padding.x,
+            theme.padding.y,
+        ];
+        if sizes.iter().any(|size| !size.is_finite() || *size < 0.0) {
+            anyhow::bail!("Theme sizes must not be negative");
+        }
+
+        Ok(theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_preset_names() {
+        assert_eq!(
+            "Windows 11".parse::<ThemePreset>().unwrap(),
+            ThemePreset::Windows11
+        );
+        assert_eq!(
+            "high_contrast".parse::<ThemePreset>().unwrap(),
+            ThemePreset::HighContrast
+        );
+
+        let error = "neon".parse::<ThemePreset>().unwrap_err().to_string();
+        assert!(error.contains("`minimal`"), "{error}");
+    }
+
+    #[test]
+    fn applies_config_over_preset() {
+        let theme: Theme = toml::from_str(
+            r##"
+            preset = "minimal"
+            radius = 3
+            padding = [10, 4]
+
+            [colors]
+            indicator = "#ff8800"
+            "##,
+        )
+        .unwrap();
+
+        let minimal = ThemePreset::Minimal.theme();
+        assert_eq!(theme.radius, 3.0);
+        assert_eq!(theme.padding, vec2(10.0, 4.0));
+        assert_eq!(theme.min_size, minimal.min_size);
+        assert_eq!(theme.colors.indicator, Some(Color32::from_rgb(255, 136, 0)));
+        assert_eq!(theme.colors.background, Some(Color32::TRANSPARENT));
+
+        let error = |text: &str| toml::from_str::<Theme>(text).unwrap_err().to_string();
+        assert!(error("radius = -1").contains("must not be negative"));
+        assert!(error("[colors]\ntext = \"red\"").contains("Invalid color `red`"));
+        assert!(error("rounding = 2").contains("unknown field `rounding`"));
+    }
+
+    #[test]
+    fn falls_back_to_system_colors() {
+        let accent = Color32::from_rgb(0, 120, 212);
+        let system = SystemColors {
+            dark_mode: true,
+            accent: Some(accent),
+            foreground: None,
+        };
+
+        let palette = Theme::default().palette(&system);
+        assert_eq!(palette.indicator, accent);
+        assert_eq!(palette.text, Color32::WHITE);
+
+        let light = SystemColors {
+            dark_mode: false,
+            ..system
+        };
+        assert_eq!(Theme::default().palette(&light).text, Color32::BLACK);
+
+        let high_contrast = ThemePreset::HighContrast.theme().palette(&light);
+        assert_eq!(high_contrast.text, Color32::WHITE);
+        assert_eq!(high_contrast.indicator, accent);
+    }
+}
@@ -0,0 +1,435 @@
+//! The button of a workspace in the switcher, drawn with a [`Theme`].
+
+use crate::label::{LabelContext, LabelTemplate};
+use crate::state::{TilingDirection, Workspace};
+use crate::theme::{SystemColors, Theme};
+
+pub struct WorkspaceButton<'a> {
+    workspace: &'a Workspace,
+    theme: &'a Theme,
+    label: String,
+    text_color: Option<egui::Color32>,
+    line_on_top: bool,
+    line_focused_color: Option<egui::Color32>,
+    dark_mode: Option<bool>,
+    window_count: Option<usize>,
+    tiling_direction: Option<TilingDirection>,
+    drop_target: bool,
+}
+
+/// What was interacted with in a [`WorkspaceButton`].
+pub struct WorkspaceButtonResponse {
+    pub button: egui::Response,
+    /// The tiling direction arrow, when shown.
+    pub tiling_direction: Option<egui::Response>,
+}
+
+impl<'a> WorkspaceButton<'a> {
+    pub fn new(workspace: &'a Workspace, theme: &'a Theme) -> Self {
+        Self {
+            workspace,
+            theme,
+            label: workspace.name.clone(),
+            text_color: None,
+            line_on_top: false,
+            line_focused_color: None,
+            dark_mode: None,
+            window_count: None,
+            tiling_direction: None,
+            drop_target: false,
+        }
+    }
+
+    pub fn dark_mode(mut self, dark_mode: Option<bool>) -> Self {
+        self.dark_mode = dark_mode;
+        self
+    }
+
+    // pub fn text_color(mut self, olor: egui::Color32) -> Self {
+    //     self.text_color.replace(color);
+    //     self
+    // }
+
+    pub fn text_color_opt(mut self, color: Option<egui::Color32>) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn line_on_top(mut self, line_on_top: bool) -> Self {
+        self.line_on_top = line_on_top;
+        self
+    }
+
+    // pub fn line_focused_color(mut self, color: egui::Color32) -> Self {
+    //     self.line_focused_color.replace(color);
+    //     self
+    // }
+
+    pub fn line_focused_color_opt(mut self, color: Option<egui::Color32>) -> Self {
+        self.line_focused_color = color;
+        self
+    }
+
+    /// Labels the button with `template`, for the workspace at `index` on its
+    /// monitor, shown as `alias` if it has one.
+    pub fn label(mut self, template: &LabelTemplate, index: usize, alias: Option<&str>) -> Self {
+        self.label = template.render(&LabelContext {
+            workspace: self.workspace,
+            index,
+            alias,
+        });
+        self
+    }
+
+    /// Shows an arrow for the tiling direction of the focused workspace.
+    pub fn show_tiling_direction(mut self) -> Self {
+        self.tiling_direction = self
+            .workspace
+            .tiling_direction
+            .filter(|_| self.workspace.focused);
+        self
+    }
+
+    /// Highlights the button as the place a dragged window would be dropped.
+    pub fn drop_target(mut self, drop_target: bool) -> Self {
+        self.drop_target = drop_target;
+        self
+    }
+
+    /// Shows the workspace window count as a badge in the top right corner.
+    pub fn show_window_count(mut self, show: bool) -> Self {
+        self.window_count = show.then_some(self.workspace.window_count);
+        self
+    }
+}
+
+impl egui::Widget for WorkspaceButton<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        self.show(ui).button
+    }
+}
+
+impl WorkspaceButton<'_> {
+    pub fn show(self, ui: &mut egui::Ui) -> WorkspaceButtonResponse {
+        const BADGE_FONT_SIZE: f32 = 9.0;
+        const BADGE_PADDING: egui::Vec2 = egui::vec2(3.0, 1.0);
+        const ARROW_WIDTH: f32 = 12.0;
+        const ARROW_LENGTH: f32 = 6.0;
+
+        let theme = self.theme;
+        let palette = theme.palette(&SystemColors {
+            dark_mode: self.dark_mode.unwrap_or_else(|| ui.visuals().dark_mode),
+            accent: self.line_focused_color,
+            foreground: self.text_color,
+        });
+
+        let font_id = egui::FontId::default();
+        let text_color = palette.text;
+
+        let text = self.label.clone();
+        let text_galley = ui
+            .painter()
+            .layout_no_wrap(text, font_id.clone(), text_color);
+
+        let arrow_width = if self.tiling_direction.is_some() {
+            ARROW_WIDTH
+        } else {
+            0.0
+        };
+
+        let size = theme
+            .min_size
+            .max(text_galley.rect.size() + theme.padding + egui::vec2(arrow_width, 0.0));
+
+        let (rect, response) = ui.allocate_at_least(size, egui::Sense::click_and_drag());
+
+        // the arrow takes the right edge, the text stays centered in the rest
+        let text_rect = rect.with_max_x(rect.max.x - arrow_width);
+        let arrow_response = self.tiling_direction.map(|_| {
+            let arrow_rect = rect.with_min_x(text_rect.max.x);
+            ui.interact(
+                arrow_rect,
+                response.id.with("tiling_direction"),
+                egui::Sense::CLICK,
+            )
+        });
+
+        let painter = ui.painter();
+
+        // draw background
+        if self.drop_target {
+            let color = palette.indicator;
+            let stroke = egui::Stroke::new(1.0, color);
+            painter.rect(
+                rect,
+                theme.radius,
+                color.gamma_multiply(0.25),
+                stroke,
+                egui::StrokeKind::Inside,
+            );
+        } else if response.hovered() || self.workspace.focused {
+            let color = if self.workspace.focused {
+                palette.background
+            } else {
+                palette.hover
+            };
+
+            let stroke = egui::Stroke {
+                width: 1.0,
+                color: palette.stroke,
+            };
+
+            painter.rect(rect, theme.radius, color, stroke, egui::StrokeKind::Inside);
+        }
+
+        // draw indicator
+
+        // animate opacity
+        let target_opacity = (self.workspace.focused || !self.workspace.is_empty) as i32 as f32;
+        let opacity = egui_animation::animate_eased(
+            ui.ctx(),
+            format!("Opacity{}", self.workspace.name),
+            target_opacity,
+            0.3,
+            egui_animation::easing::sine_out,
+        );
+
+        // animate width
+        let target_line_width = if !response.is_pointer_button_down_on() && self.workspace.focused {
+            theme.indicator_focused_width
+        } else {
+            theme.indicator_width
+        };
+        let line_width = egui_animation::animate_eased(
+            ui.ctx(),
+            format!("Width{}", self.workspace.name),
+            target_line_width,
+            0.2,
+            egui_animation::easing::sine_out,
+        );
+
+        if line_width != target_line_width || opacity != target_opacity {
+            ui.ctx().request_repaint();
+        }
+
+        let x = rect.center().x - line_width / 2.0;
+        let mut line_rect = rect.with_min_x(x).with_max_x(x + line_width);
+
+        if self.line_on_top {
+            line_rect = line_rect.with_max_y(rect.min.y + theme.indicator_height);
+        } else {
+            line_rect = line_rect.with_min_y(rect.max.y - theme.indicator_height);
+        };
+
+        let color = if self.workspace.focused {
+            palette.indicator
+        } else {
+            palette.inactive_indicator
+        };
+
+        painter.rect_filled(line_rect, theme.radius, color.gamma_multiply(opacity));
+
+        // draw text
+        let text_color = if response.hovered() || self.workspace.focused {
+            text_color
+        } else {
+            text_color.gamma_multiply(0.75)
+        };
+
+        painter.text(
+            text_rect.center(),
+            egui::Align2::CENTER_CENTER,
+            &self.label,
+            font_id,
+            text_color,
+        );
+
+        // draw tiling direction arrow
+        if let (Some(direction), Some(arrow)) = (self.tiling_direction, &arrow_response) {
+            let vec = match direction {
+                TilingDirection::Horizontal => egui::vec2(ARROW_LENGTH, 0.0),
+                TilingDirection::Vertical => egui::vec2(0.0, ARROW_LENGTH),
+            };
+            let color = if arrow.hovered() {
+                text_color
+            } else {
+                text_color.gamma_multiply(0.6)
+            };
+            // sit closer to the text than to the button edge
+            let center = arrow.rect.center() - egui::vec2(theme.padding.x / 4.0, 0.0);
+            painter.arrow(center - vec / 2.0, vec, egui::Stroke::new(1.0, color));
+        }
+
+        // draw window count badge
+        if let Some(count) = self.window_count.filter(|c| *c > 0) {
+            painter.text(
+                rect.right_top() + egui::vec2(-BADGE_PADDING.x, BADGE_PADDING.y),
+                egui::Align2::RIGHT_TOP,
+                count.to_string(),
+                egui::FontId::proportional(BADGE_FONT_SIZE),
+                text_color.gamma_multiply(0.75),
+            );
+        }
+
+        WorkspaceButtonResponse {
+            button: response,
+            tiling_direction: arrow_response,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use egui::{vec2, Color32, Pos2, RawInput, Rect, Shape};
+
+    use super::*;
+    use crate::theme::ThemePreset;
+
+    fn hex(color: Color32) -> String {
+        color.to_hex()
+    }
+
+    fn describe(shape: &Shape, out: &mut String) {
+        let rect = |r: Rect| format!("[{} {} {} {}]", r.min.x, r.min.y, r.max.x, r.max.y);
+        match shape {
+            Shape::Noop => {}
+            Shape::Vec(shapes) => shapes.iter().for_each(|s| describe(s, out)),
+            Shape::Rect(r) => writeln!(
+                out,
+                "rect {} radius {} fill {} stroke {} {}",
+                rect(r.rect),
+                r.corner_radius.nw,
+                hex(r.fill),
+                r.stroke.width,
+                hex(r.stroke.color)
+            )
+            .unwrap(),
+            Shape::Text(t) => writeln!(
+                out,
+                "text {:?} {} color {}",
+                t.galley.text(),
+                rect(t.visual_bounding_rect()),
+                hex(t.fallback_color)
+            )
+            .unwrap(),
+            Shape::LineSegment { points, stroke } => writeln!(
+                out,
+                "line {:?} {:?} stroke {} {}",
+                points[0],
+                points[1],
+                stroke.width,
+                hex(stroke.color)
+            )
+            .unwrap(),
+            other => writeln!(out, "{other:?}").unwrap(),
+        }
+    }
+
+    fn render(theme: &Theme, dark_mode: bool) -> String {
+        render_with(theme, dark_mode, &LabelTemplate::default(), None)
+    }
+
+    /// Draws a focused workspace with a tiling direction, one holding
+    /// windows, an empty one and a drop target, listing the painted shapes.
+    /// `web_alias` is the alias of the workspace holding windows.
+    fn render_with(
+        theme: &Theme,
+        dark_mode: bool,
+        label: &LabelTemplate,
+        web_alias: Option<&str>,
+    ) -> String {
+        let workspace = |name: &str| Workspace {
+            name: name.into(),
+            is_empty: true,
+            ..Default::default()
+        };
+        let workspaces = [
+            Workspace {
+                focused: true,
+                is_empty: false,
+                window_count: 2,
+                tiling_direction: Some(TilingDirection::Horizontal),
+                ..workspace("1")
+            },
+            Workspace {
+                is_empty: false,
+                window_count: 1,
+                ..workspace("web")
+            },
+            workspace("3"),
+            workspace("4"),
+        ];
+
+        let ctx = egui::Context::default();
+        let run = || {
+            let input = RawInput {
+                screen_rect: Some(Rect::from_min_size(Pos2::ZERO, vec2(400.0, 40.0))),
+                ..Default::default()
+            };
+            ctx.run(input, |ctx| {
+                egui::Area::new("switcher".into())
+                    .fixed_pos(Pos2::ZERO)
+                    .fade_in(false)
+                    .show(ctx, |ui| {
+                        ui.horizontal(|ui| {
+                            for (index, workspace) in workspaces.iter().enumerate() {
+                                let alias = web_alias.filter(|_| workspace.name == "web");
+                                WorkspaceButton::new(workspace, theme)
+                                    .label(label, index, alias)
+                                    .dark_mode(Some(dark_mode))
+                                    .line_focused_color_opt(Some(Color32::from_rgb(0, 120, 212)))
+                                    .show_window_count(true)
+                                    .show_tiling_direction()
+                                    .drop_target(workspace.name == "4")
+                                    .show(ui);
+                            }
+                        });
+                    });
+            })
+        };
+
+        // areas are laid out invisibly in their first frame
+        run();
+        let output = run();
+
+        let mut out = String::new();
+        for clipped in &output.shapes {
+            describe(&clipped.shape, &mut out);
+        }
+        out
+    }
+
+    #[test]
+    fn renders_presets() {
+        for preset in ThemePreset::ALL {
+            insta::assert_snapshot!(preset.name(), render(&preset.theme(), true));
+        }
+        insta::assert_snapshot!("windows-11-light", render(&Theme::default(), false));
+    }
+
+    #[test]
+    fn renders_config_overrides() {
+        let theme: Theme = toml::from_str(
+            r##"
+            radius = 0
+            min_size = [40, 30]
+            indicator_height = 1
+
+            [colors]
+            text = "#ffcc00"
+            indicator = "#00ff00"
+            "##,
+        )
+        .unwrap();
+
+        insta::assert_snapshot!(render(&theme, true));
+    }
+
+    #[test]
+    fn renders_label_templates() {
+        let label = "{index}:{display_name}[ ({window_count})]".parse().unwrap();
+        insta::assert_snapshot!(render_with(&Theme::default(), true, &label, Some("www")));
+    }
+}
@@ -1,3 +1,6 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use windows::Win32::Foundation::HWND;
 use winit::application::ApplicationHandler;
 use winit::event::{StartCause, WindowEvent};
@@ -7,11 +10,15 @@ use winit::window::WindowId;
 use crate::egui_glue::EguiWindow;
 use crate::utils::{MultiMap, RECTExt};
 use crate::window_registry_info::WindowRegistryInfo;
-use crate::options::Options;
+use switcher_core::cli::Cli;
+use switcher_core::connection::ConnectionState;
+use switcher_core::options::Options;
+use switcher_core::provider::{ProviderEvent, WorkspaceProvider};
 
 #[derive(Debug, Clone)]
 pub enum AppMessage {
-    UpdateState(crate::state::State),
+    UpdateState(switcher_core::state::State),
+    ConnectionChanged(ConnectionState),
     MenuEvent(muda::MenuEvent),
     SystemSettingsChanged,
     DpiChanged,
@@ -23,38 +30,99 @@ pub enum AppMessage {
         window_id: WindowId,
     },
     CloseWindow(WindowId),
+    TogglePause,
+    /// The config file changed on disk.
+    OptionsChanged(Box<Options>),
+    /// A window started moving, it may be dropped on a workspace button.
+    WindowDragStarted,
+    WindowDragEnded,
+    /// A workspace button was dropped outside its switcher, at a screen point
+    /// in physical pixels.
+    DropWorkspace {
+        monitor_idx: usize,
+        workspace: String,
+        x: i32,
+        y: i32,
+    },
     NotifyWindowInfoChanges(WindowId, WindowRegistryInfo),
 }
 
+/// How often the config file is checked for changes.
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 pub struct App {
     pub wgpu_instance: wgpu::Instance,
     pub proxy: EventLoopProxy<AppMessage>,
     pub windows: MultiMap<WindowId, Option<String>, EguiWindow>,
     pub tray_icon: Option<crate::tray_icon::TrayIcon>,
-    pub state: crate::state::State,
-    pub change_workspace_fn: fn(usize, usize),
+    pub state: switcher_core::state::State,
+    pub connection: ConnectionState,
+    pub provider: Arc<dyn WorkspaceProvider>,
     pub options: Options,
+    /// Options the provider was built with, to tell which reloaded options
+    /// only apply after a restart.
+    pub startup_options: Options,
 }
 
 impl App {
-    pub fn new(proxy: EventLoopProxy<AppMessage>) -> anyhow::Result<Self> {
+    pub fn new(
+        proxy: EventLoopProxy<AppMessage>,
+        options: Options,
+        cli: Cli,
+        provider: Arc<dyn WorkspaceProvider>,
+    ) -> anyhow::Result<Self> {
         let wgpu_instance = egui_wgpu::wgpu::Instance::new(&wgpu::InstanceDescriptor {
             backends: wgpu::Backends::DX12,
             ..Default::default()
         });
 
-        let tray_icon = crate::tray_icon::TrayIcon::new(proxy.clone()).ok();
-        let options = Options::from_env();
+        let state = provider.snapshot().unwrap_or_else(|e| {
+            tracing::error!("Failed to read {} state: {e}", provider.name());
+            Default::default()
+        });
+
+        let tray_icon =
+            crate::tray_icon::TrayIcon::new(proxy.clone(), provider.as_ref(), state.paused).ok();
 
-        // GlazeWM-only: read initial state and start listener
-        let (state, change_workspace_fn) = match crate::glazewm::read_state() {
-            Ok(state) => {
-                let proxy_clone = proxy.clone();
-                std::thread::spawn(move || crate::glazewm::listen_for_state(proxy_clone));
-                (state, crate::glazewm::change_workspace as fn(usize, usize))
+        if let Err(e) = crate::window_drag::watch(proxy.clone()) {
+            tracing::error!("Failed to watch window drags: {e}");
+        }
+
+        // Reload the options when the config file changes, until the app exits
+        let proxy_clone = proxy.clone();
+        let config_path = cli.config_path()?;
+        switcher_core::options::watch(config_path, CONFIG_POLL_INTERVAL, move |options| {
+            match options {
+                Ok(mut options) => {
+                    cli.apply_overrides(&mut options);
+                    let message = AppMessage::OptionsChanged(Box::new(options));
+                    proxy_clone.send_event(message).is_ok()
+                }
+                Err(e) => {
+                    tracing::warn!("Keeping the previous options: {e:#}");
+
+                    // don't block the watcher until the dialog is closed
+                    let message = format!("{e:#}");
+                    std::thread::spawn(move || crate::error_dialog(message));
+                    true
+                }
             }
-            Err(_) => (Default::default(), crate::glazewm::change_workspace as fn(usize, usize)),
-        };
+        });
+
+        let proxy_clone = proxy.clone();
+        switcher_core::provider::spawn_subscription(
+            provider.clone(),
+            options.backoff(),
+            Box::new(move |event| {
+                let message = match event {
+                    ProviderEvent::State(state) => AppMessage::UpdateState(state),
+                    ProviderEvent::Connection(state) => AppMessage::ConnectionChanged(state),
+                };
+                if let Err(e) = proxy_clone.send_event(message) {
+                    tracing::error!("Failed to send `AppMessage`: {e}")
+                }
+            }),
+        );
 
         Ok(Self {
             wgpu_instance,
@@ -62,7 +130,9 @@ impl App {
             proxy,
             tray_icon,
             state,
-            change_workspace_fn,
+            connection: ConnectionState::default(),
+            provider,
+            startup_options: options.clone(),
             options,
         })
     }
@@ -72,6 +142,14 @@ impl App {
 
         tracing::debug!("Found {} taskbars: {taskbars:?}", taskbars.len());
 
+        let taskbar_rects = taskbars
+            .iter()
+            .map(|tb| tb.rect.to_rect())
+            .collect::<Vec<_>>();
+        let primary = taskbars.iter().position(|tb| {
+            crate::utils::get_class_name(tb.hwnd) == crate::taskbar::TASKBAR_CLASS_NAME
+        });
+
         for monitor in self.state.monitors.clone().into_iter() {
             // skip already existing window for this monitor
             let monitor_id = monitor.id.clone();
@@ -79,41 +157,31 @@ impl App {
                 continue;
             }
 
-            // Try to find a taskbar whose rect is contained within the monitor rect.
-            // If the monitor rect is empty (e.g., GlazeWM default), map by monitor index to taskbar index.
-            let selected_taskbar = if let Some(tb) = taskbars.iter().find(|tb| monitor.rect.contains(&tb.rect)) {
-                Some(tb)
-            } else {
-                tracing::warn!(
-                    "Failed to find taskbar for monitor: {}-{} {:?}",
-                    monitor.name,
-                    monitor.id,
-                    monitor.rect
-                );
-
-                // Map empty monitor rects to taskbars by index to ensure one switcher per taskbar.
-                taskbars.get(monitor.index).or_else(|| {
-                    // Prefer the primary taskbar class name; otherwise pick the first available taskbar
-                    let primary = taskbars
-                        .iter()
-                        .find(|tb| crate::utils::get_class_name(tb.hwnd) == crate::taskbar::TASKBAR_CLASS_NAME);
-                    primary.or_else(|| taskbars.first())
-                })
-            };
-
-            let Some(taskbar) = selected_taskbar else {
+            let Some(taskbar) =
+                switcher_core::layout::taskbar_for_monitor(&monitor, &taskbar_rects, primary)
+                    .and_then(|i| taskbars.get(i))
+            else {
                 continue;
             };
 
             tracing::info!(
-                "Creating switcher window for monitor: {}-{} {:?} on taskbar: {:?}",
+                "Creating switcher window for monitor: {}-{} {:?} (dpi {}) on taskbar: {:?}",
                 monitor.name,
                 monitor.id,
                 monitor.rect,
+                monitor.dpi,
                 taskbar.hwnd
             );
 
-            let window = self.create_switcher_window(event_loop, *taskbar, monitor, self.options, self.change_workspace_fn)?;
+            let window = self.create_switcher_window(
+                event_loop,
+                *taskbar,
+                monitor,
+                &self.state,
+                self.connection,
+                &self.options,
+                self.provider.clone(),
+            )?;
 
             self.windows.insert(window.id(), Some(monitor_id), window);
         }
@@ -121,6 +189,23 @@ impl App {
         Ok(())
     }
 
+    /// Adds a `Move & Resize` tray item for every monitor that has a switcher.
+    fn create_tray_items(&mut self) -> anyhow::Result<()> {
+        let Some(tray) = &mut self.tray_icon else {
+            return Ok(());
+        };
+
+        let switchers = self
+            .state
+            .monitors
+            .iter()
+            .filter(|m| self.windows.contains_key_alt(&Some(m.id.clone())))
+            .map(|m| (m.id.clone(), format!("{} - {}", m.name, m.id)))
+            .collect::<Vec<_>>();
+
+        tray.create_items_for_switchers(switchers)
+    }
+
     fn handle_app_message(
         &mut self,
         event_loop: &ActiveEventLoop,
@@ -169,15 +254,63 @@ impl App {
                 // Update tray icon
                 if let Some(tray) = &mut self.tray_icon {
                     tray.destroy_items_for_switchers()?;
+                }
+                self.create_tray_items()?;
+            }
+
+            AppMessage::OptionsChanged(options) => {
+                // only warn when the pending changes differ from the last reload
+                let changes = self.startup_options.restart_changes(options);
+                let pending = self.startup_options.restart_changes(&self.options);
+                if !changes.is_empty() && changes != pending {
+                    let message = format!(
+                        "Restart {} to apply the changes to {}",
+                        env!("CARGO_PKG_NAME"),
+                        changes.join(", ")
+                    );
+                    tracing::warn!("{message}");
+                    std::thread::spawn(move || crate::error_dialog(message));
+                }
+
+                self.options = Options::clone(options);
+            }
 
-                    let switchers_ids = self
-                        .state
-                        .monitors
-                        .iter()
-                        .filter(|m| self.windows.contains_key_alt(&Some(m.id.clone())))
-                        .map(|m| format!("{}-{}", m.name, m.id))
-                        .collect::<Vec<_>>();
-                    tray.create_items_for_switchers(switchers_ids)?;
+            AppMessage::TogglePause => {
+                if let Err(e) = self.provider.toggle_pause() {
+                    tracing::warn!("Failed to toggle pause: {e}");
+
+                    let message =
+                        format!("Failed to pause or resume {}: {e}", self.provider.name());
+                    std::thread::spawn(move || crate::error_dialog(message));
+                }
+            }
+
+            AppMessage::DropWorkspace {
+                monitor_idx,
+                workspace,
+                x,
+                y,
+            } => {
+                let target = switcher_core::layout::monitor_at(&self.state.monitors, *x, *y);
+                if let Some(target) = target.filter(|m| m.index != *monitor_idx) {
+                    if let Err(e) = self.provider.move_workspace_to_monitor(
+                        *monitor_idx,
+                        workspace,
+                        target.index,
+                    ) {
+                        tracing::warn!(
+                            "Failed to move workspace {workspace:?} to {}: {e}",
+                            target.name
+                        );
+                    }
+                }
+            }
+
+            AppMessage::ConnectionChanged(connection) => {
+                self.connection = *connection;
+
+                if let Some(tray) = &self.tray_icon {
+                    tray.set_status(&connection.describe(self.provider.name()))?;
                 }
             }
 
@@ -195,15 +328,8 @@ impl ApplicationHandler<AppMessage> for App {
                 tracing::error!("Error while creating switchers: {e}");
             };
 
-            if let Some(tray) = &mut self.tray_icon {
-                let switchers_ids = self
-                    .windows
-                    .iter()
-                    .filter_map(|(_, (key, _))| key.clone())
-                    .collect::<Vec<_>>();
-                if let Err(e) = tray.create_items_for_switchers(switchers_ids) {
-                    tracing::error!("Error while creating tray items for switchers: {e}");
-                }
+            if let Err(e) = self.create_tray_items() {
+                tracing::error!("Error while creating tray items for switchers: {e}");
             }
         }
     }
@@ -2,6 +2,8 @@
 
 use std::fmt::Display;
 
+use clap::Parser;
+use switcher_core::cli::{Cli, Command};
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::EnvFilter;
 use winit::event_loop::EventLoop;
@@ -10,13 +12,11 @@ use crate::app::{App, AppMessage};
 
 mod app;
 mod egui_glue;
-mod glazewm;
-mod state;
-mod options;
 mod taskbar;
 mod tray_icon;
 mod utils;
 mod widgets;
+mod window_drag;
 mod window_registry_info;
 mod windows;
 
@@ -29,7 +29,28 @@ fn error_dialog<T: Display>(error: T) {
         .show();
 }
 
-fn run() -> anyhow::Result<()> {
+/// Attaches to the console the switcher was started from, if any, so the
+/// subcommands can print. Release builds have no console of their own.
+fn attach_console() {
+    use ::windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+fn run_command(cli: &Cli, command: Command) -> anyhow::Result<()> {
+    match command {
+        Command::Version => println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        Command::DumpState => println!("{}", switcher_core::cli::dump_state(&cli.options()?)?),
+        Command::Check => {
+            let report = switcher_core::cli::check(&cli.config_path()?, &cli.options()?)?;
+            println!("{report}");
+        }
+    }
+
+    Ok(())
+}
+
+fn run(cli: &Cli) -> anyhow::Result<()> {
     let evl = EventLoop::<AppMessage>::with_user_event().build()?;
 
     let proxy = evl.create_proxy();
@@ -39,75 +60,41 @@ fn run() -> anyhow::Result<()> {
         }
     }));
 
-    let mut app = App::new(evl.create_proxy())?;
+    let options = cli.options()?;
+    let provider = switcher_core::provider::create(&options)?;
+    let mut app = App::new(evl.create_proxy(), options, cli.clone(), provider)?;
     evl.run_app(&mut app)?;
 
     Ok(())
 }
 
 fn main() -> anyhow::Result<()> {
-    // In debug, avoid console logs by default and log to a file.
-    // In release, avoid console logs and log to a daily-rotating file.
-
-    #[cfg(debug_assertions)]
-    let env_filter = EnvFilter::try_from_env("KOMOREBI_SWITCHER_LOG").unwrap_or_else(|_| {
-        EnvFilter::builder()
-            .with_default_directive(LevelFilter::DEBUG.into())
-            .from_env_lossy()
-    });
-
-    #[cfg(not(debug_assertions))]
-    let env_filter = EnvFilter::try_from_env("KOMOREBI_SWITCHER_LOG").unwrap_or_else(|_| {
-        EnvFilter::builder()
-            .with_default_directive(LevelFilter::INFO.into())
-            .from_env_lossy()
-    });
-
-    #[cfg(debug_assertions)]
-    let (file_log_layer, _f_guard) = {
-        use std::time::{Duration, SystemTime};
-        use anyhow::Context;
-
-        let logs_dir = dirs::data_dir()
-            .context("Failed to get $data_dir path")?
-            .join("wm-workspace")
-            .join("logs");
-
-        const MONTH: Duration = Duration::from_secs(60 * 60 * 24 * 30);
-        let now = SystemTime::now();
-
-        // remove logs older than a month
-        for entry in std::fs::read_dir(&logs_dir).ok().into_iter().flatten() {
-            let Ok(entry) = entry else {
-                continue;
-            };
-            let Ok(modified_time) = entry.metadata().and_then(|m| m.modified()) else {
-                continue;
-            };
-
-            let elapsed = now.duration_since(modified_time).unwrap_or_default();
-            if elapsed > MONTH {
-                let _ = std::fs::remove_file(entry.path());
-            }
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            attach_console();
+            e.exit();
         }
-
-        let appender = tracing_appender::rolling::daily(&logs_dir, "wm-workspace.log");
-        let (non_blocking, _guard) = tracing_appender::non_blocking(appender);
-        let layer = tracing_subscriber::fmt::Layer::default()
-            .with_ansi(false)
-            .with_writer(non_blocking);
-
-        (layer, _guard)
     };
 
-    #[cfg(debug_assertions)]
-    use tracing_subscriber::{layer::SubscriberExt, Registry};
-    #[cfg(debug_assertions)]
-    let subscriber = Registry::default()
-        .with(env_filter)
-        .with(file_log_layer);
+    // Log to a daily-rotating file, release builds have no console.
+    let default_level = if cfg!(debug_assertions) {
+        LevelFilter::DEBUG
+    } else {
+        LevelFilter::INFO
+    };
+    let env_filter = match cli.log_level {
+        Some(level) => EnvFilter::default().add_directive(level.into()),
+        None => EnvFilter::try_from_env("SWITCHER_LOG")
+            // the name used before the switcher supported other window managers
+            .or_else(|_| EnvFilter::try_from_env("KOMOREBI_SWITCHER_LOG"))
+            .unwrap_or_else(|_| {
+                EnvFilter::builder()
+                    .with_default_directive(default_level.into())
+                    .from_env_lossy()
+            }),
+    };
 
-    #[cfg(not(debug_assertions))]
     let (file_log_layer, _f_guard) = {
         use std::time::{Duration, SystemTime};
 
@@ -146,23 +133,29 @@ fn main() -> anyhow::Result<()> {
         (layer, _guard)
     };
 
-    #[cfg(not(debug_assertions))]
     use tracing_subscriber::{layer::SubscriberExt, Registry};
-    #[cfg(not(debug_assertions))]
-    let subscriber = Registry::default()
-        .with(env_filter)
-        .with(file_log_layer);
+    let subscriber = Registry::default().with(env_filter).with(file_log_layer);
 
     tracing::subscriber::set_global_default(subscriber)?;
 
     tracing::debug!("Initialized Logger");
 
+    if let Some(command) = cli.command {
+        attach_console();
+        if let Err(e) = run_command(&cli, command) {
+            eprintln!("Error: {e:#}");
+            tracing::error!("{e:#}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     std::panic::set_hook(Box::new(|info| {
         error_dialog(info);
         tracing::error!("{info}");
     }));
 
-    if let Err(e) = run() {
+    if let Err(e) = run(&cli) {
         error_dialog(&e);
         tracing::error!("{e}");
         std::process::exit(1);
@@ -4,25 +4,43 @@ use tray_icon::TrayIconBuilder;
 use winit::event_loop::{ActiveEventLoop, EventLoopProxy};
 
 use crate::app::AppMessage;
+use switcher_core::provider::WorkspaceProvider;
 
 pub struct TrayIcon {
-    #[allow(unused)]
     icon: tray_icon::TrayIcon,
     proxy: EventLoopProxy<AppMessage>,
     #[allow(unused)]
     menu: Menu,
     quit: MenuItem,
+    /// Only there when the window manager can be paused.
+    pause: Option<MenuItem>,
+    provider_name: String,
     move_resize: Submenu,
     move_resize_items: Vec<MenuItem>,
 }
 
 impl TrayIcon {
-    pub fn new(proxy: EventLoopProxy<AppMessage>) -> anyhow::Result<Self> {
+    pub fn new(
+        proxy: EventLoopProxy<AppMessage>,
+        provider: &dyn WorkspaceProvider,
+        paused: bool,
+    ) -> anyhow::Result<Self> {
         let icon = tray_icon::Icon::from_resource(1, Some((32, 32)))?;
 
         let quit = MenuItem::new("Quit", true, None);
         let move_resize = Submenu::new("Move && Resize", true);
-        let menu = Menu::with_items(&[&move_resize, &quit])?;
+        let pause = provider.supports_pause().then(|| {
+            MenuItem::new(
+                crate::utils::pause_menu_text(provider.name(), paused),
+                true,
+                None,
+            )
+        });
+        let menu = Menu::with_items(&[&move_resize])?;
+        if let Some(pause) = &pause {
+            menu.append(pause)?;
+        }
+        menu.append(&quit)?;
 
         TrayIconBuilder::new()
             .with_icon(icon)
@@ -35,11 +53,19 @@ impl TrayIcon {
                 proxy,
                 menu,
                 quit,
+                pause,
+                provider_name: provider.name().to_string(),
                 move_resize,
                 move_resize_items: vec![],
             })
     }
 
+    /// Shows `status` in the tooltip, after the app name.
+    pub fn set_status(&self, status: &str) -> anyhow::Result<()> {
+        let tooltip = format!("{}\n{status}", std::env!("CARGO_PKG_NAME"));
+        self.icon.set_tooltip(Some(tooltip)).map_err(Into::into)
+    }
+
     pub fn destroy_items_for_switchers(&mut self) -> anyhow::Result<()> {
         for item in &self.move_resize_items {
             self.move_resize.remove(item)?;
@@ -50,9 +76,13 @@ impl TrayIcon {
         Ok(())
     }
 
-    pub fn create_items_for_switchers(&mut self, switchers: Vec<String>) -> anyhow::Result<()> {
-        for switcher in switchers {
-            let item = MenuItem::with_id(&switcher, &switcher, true, None);
+    /// Creates one item per switcher from `(monitor id, label)` pairs.
+    pub fn create_items_for_switchers(
+        &mut self,
+        switchers: Vec<(String, String)>,
+    ) -> anyhow::Result<()> {
+        for (id, label) in switchers {
+            let item = MenuItem::with_id(id, label, true, None);
             self.move_resize.append(&item)?;
             self.move_resize_items.push(item);
         }
@@ -67,6 +97,17 @@ impl TrayIcon {
     ) -> anyhow::Result<()> {
         match event {
             AppMessage::MenuEvent(event) if event.id() == self.quit.id() => event_loop.exit(),
+            AppMessage::MenuEvent(event)
+                if self.pause.as_ref().is_some_and(|p| event.id() == p.id()) =>
+            {
+                self.proxy.send_event(AppMessage::TogglePause)?;
+            }
+            AppMessage::UpdateState(state) => {
+                if let Some(pause) = &self.pause {
+                    let text = crate::utils::pause_menu_text(&self.provider_name, state.paused);
+                    pause.set_text(text);
+                }
+            }
             AppMessage::MenuEvent(event)
                 if self
                     .move_resize_items
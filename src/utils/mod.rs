@@ -1,10 +1,17 @@
-mod multi_map;
-
-pub use multi_map::MultiMap;
+pub use switcher_core::MultiMap;
 use windows::core::*;
 use windows::Win32::Foundation::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
 
+/// Text of the menu items pausing or resuming the window manager.
+pub fn pause_menu_text(provider_name: &str, paused: bool) -> String {
+    if paused {
+        format!("Resume {provider_name}")
+    } else {
+        format!("Pause {provider_name}")
+    }
+}
+
 pub fn enum_child_windows(hwnd: HWND) -> Vec<HWND> {
     let mut children = Vec::new();
 
@@ -60,14 +67,16 @@ impl Iterator for TopLevelWindowsIterator {
 }
 
 pub trait RECTExt {
-    fn contains(&self, other: &RECT) -> bool;
+    fn to_rect(&self) -> switcher_core::state::Rect;
 }
 
 impl RECTExt for RECT {
-    fn contains(&self, other: &RECT) -> bool {
-        self.left <= other.left
-            && self.top <= other.top
-            && self.right >= other.right
-            && self.bottom >= other.bottom
+    fn to_rect(&self) -> switcher_core::state::Rect {
+        switcher_core::state::Rect {
+            left: self.left,
+            top: self.top,
+            right: self.right,
+            bottom: self.bottom,
+        }
     }
 }
@@ -0,0 +1,72 @@
+use switcher_core::state::BindingMode;
+
+/// A rounded label showing an active binding mode.
+pub struct BindingModePill<'a> {
+    mode: &'a BindingMode,
+    fill_color: Option<egui::Color32>,
+    text_color: Option<egui::Color32>,
+    dark_mode: Option<bool>,
+}
+
+impl<'a> BindingModePill<'a> {
+    pub fn new(mode: &'a BindingMode) -> Self {
+        Self {
+            mode,
+            fill_color: None,
+            text_color: None,
+            dark_mode: None,
+        }
+    }
+
+    pub fn dark_mode(mut self, dark_mode: Option<bool>) -> Self {
+        self.dark_mode = dark_mode;
+        self
+    }
+
+    pub fn fill_color_opt(mut self, color: Option<egui::Color32>) -> Self {
+        self.fill_color = color;
+        self
+    }
+
+    pub fn text_color_opt(mut self, color: Option<egui::Color32>) -> Self {
+        self.text_color = color;
+        self
+    }
+}
+
+impl egui::Widget for BindingModePill<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        const HEIGHT: f32 = 20.0;
+        const TEXT_PADDING: egui::Vec2 = egui::vec2(16.0, 0.0);
+
+        let dark_mode = self.dark_mode.unwrap_or_else(|| ui.visuals().dark_mode);
+
+        let font_id = egui::FontId::proportional(12.0);
+        let text_color = self.text_color.unwrap_or(if dark_mode {
+            egui::Color32::WHITE
+        } else {
+            egui::Color32::BLACK
+        });
+
+        let galley =
+            ui.painter()
+                .layout_no_wrap(self.mode.label().to_string(), font_id, text_color);
+
+        let size = egui::vec2(galley.rect.width() + TEXT_PADDING.x, HEIGHT);
+        let (rect, response) =
+            ui.allocate_exact_size(size, egui::Sense::CLICK | egui::Sense::HOVER);
+
+        let fill = self.fill_color.unwrap_or(egui::Color32::CYAN);
+        let fill = if response.hovered() {
+            fill
+        } else {
+            fill.gamma_multiply(0.6)
+        };
+
+        let painter = ui.painter();
+        painter.rect_filled(rect, HEIGHT / 2.0, fill);
+        painter.galley(rect.center() - galley.rect.size() / 2.0, galley, text_color);
+
+        response
+    }
+}
@@ -1,3 +1,4 @@
-mod workspace_button;
+mod binding_mode_pill;
 
-pub use workspace_button::*;
+pub use binding_mode_pill::*;
+pub use switcher_core::workspace_button::*;
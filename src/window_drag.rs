@@ -0,0 +1,64 @@
+use std::sync::OnceLock;
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Accessibility::{SetWinEventHook, HWINEVENTHOOK};
+use windows::Win32::UI::WindowsAndMessaging::*;
+use winit::event_loop::EventLoopProxy;
+
+use crate::app::AppMessage;
+
+static PROXY: OnceLock<EventLoopProxy<AppMessage>> = OnceLock::new();
+
+/// Sends [`AppMessage::WindowDragStarted`] and [`AppMessage::WindowDragEnded`]
+/// whenever a window of another process is moved or resized by its frame.
+///
+/// Must be called from the thread running the event loop, which delivers the hook.
+pub fn watch(proxy: EventLoopProxy<AppMessage>) -> anyhow::Result<()> {
+    if PROXY.set(proxy).is_err() {
+        anyhow::bail!("Window drags are already watched");
+    }
+
+    let hook = unsafe {
+        SetWinEventHook(
+            EVENT_SYSTEM_MOVESIZESTART,
+            EVENT_SYSTEM_MOVESIZEEND,
+            None,
+            Some(on_move_size),
+            0,
+            0,
+            WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+        )
+    };
+
+    if hook.is_invalid() {
+        anyhow::bail!("Failed to hook window drags");
+    }
+
+    Ok(())
+}
+
+unsafe extern "system" fn on_move_size(
+    _hook: HWINEVENTHOOK,
+    event: u32,
+    _hwnd: HWND,
+    id_object: i32,
+    _id_child: i32,
+    _thread: u32,
+    _time: u32,
+) {
+    if id_object != OBJID_WINDOW.0 {
+        return;
+    }
+
+    let message = match event {
+        EVENT_SYSTEM_MOVESIZESTART => AppMessage::WindowDragStarted,
+        EVENT_SYSTEM_MOVESIZEEND => AppMessage::WindowDragEnded,
+        _ => return,
+    };
+
+    if let Some(proxy) = PROXY.get() {
+        if let Err(e) = proxy.send_event(message) {
+            tracing::error!("Failed to send window drag `AppMessage`: {e}")
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use std::num::NonZero;
 use std::sync::Arc;
 
-use muda::{ContextMenu, Menu, MenuItem};
+use muda::{ContextMenu, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use raw_window_handle::{RawWindowHandle, Win32WindowHandle};
 use windows::Win32::Foundation::*;
+use windows::Win32::Graphics::Gdi::{ClientToScreen, ScreenToClient};
 use windows::Win32::UI::HiDpi::GetDpiForWindow;
 use windows::Win32::UI::WindowsAndMessaging::*;
 use windows::UI::ViewManagement::{UIColorType, UISettings};
@@ -15,22 +16,31 @@ use winit::window::{Window, WindowAttributes};
 use crate::app::{App, AppMessage};
 use crate::egui_glue::{EguiView, EguiWindow};
 use crate::taskbar::Taskbar;
-use crate::widgets::WorkspaceButton;
+use crate::widgets::{BindingModePill, WorkspaceButton};
 use crate::window_registry_info::WindowRegistryInfo;
-use crate::options::Options;
+use switcher_core::bindings::MouseTrigger;
+use switcher_core::connection::ConnectionState;
+use switcher_core::drag::{DragState, DropAction};
+use switcher_core::options::Options;
+use switcher_core::provider::{WorkspaceAction, WorkspaceProvider};
+use switcher_core::state::{BindingMode, State};
 
 mod host;
 
 impl App {
+    #[allow(clippy::too_many_arguments)]
     pub fn create_switcher_window(
-        &mut self,
+        &self,
         event_loop: &ActiveEventLoop,
         taskbar: Taskbar,
-        monitor_state: crate::state::Monitor,
-        options: Options,
-        change_workspace_fn: fn(usize, usize),
+        monitor_state: switcher_core::state::Monitor,
+        state: &State,
+        connection: ConnectionState,
+        options: &Options,
+        provider: Arc<dyn WorkspaceProvider>,
     ) -> anyhow::Result<EguiWindow> {
         let window_info = WindowRegistryInfo::load(&monitor_state.id)?;
+        let options = options.for_monitor(&monitor_state.id);
 
         let host = unsafe { host::create_host(taskbar.hwnd, self.proxy.clone(), &window_info) }?;
 
@@ -66,8 +76,10 @@ impl App {
             self.proxy.clone(),
             window_info,
             monitor_state,
+            state,
+            connection,
             options,
-            change_workspace_fn,
+            provider,
         )?;
 
         EguiWindow::new(window, &self.wgpu_instance, state)
@@ -78,6 +90,19 @@ struct ContextMenuState {
     menu: muda::Menu,
     quit: muda::MenuItem,
     move_resize: muda::MenuItem,
+    /// Only there when the window manager can be paused.
+    pause: Option<muda::MenuItem>,
+    /// Holds the window manager commands, if it has any.
+    submenu: Option<muda::Submenu>,
+    /// Window manager commands and the items running them.
+    commands: Vec<(muda::MenuItem, &'static str)>,
+}
+
+/// The menu of the workspace that was last right-clicked.
+struct WorkspaceMenuState {
+    menu: muda::Menu,
+    workspace: String,
+    actions: Vec<(muda::MenuItem, WorkspaceAction)>,
 }
 
 pub struct SwitcherWindowView {
@@ -86,25 +111,33 @@ pub struct SwitcherWindowView {
     taskbar: Taskbar,
     proxy: EventLoopProxy<AppMessage>,
     context_menu: ContextMenuState,
-    monitor_state: crate::state::Monitor,
+    workspace_menu: Option<WorkspaceMenuState>,
+    drag: DragState,
+    monitor_state: switcher_core::state::Monitor,
+    binding_modes: Vec<BindingMode>,
+    paused: bool,
+    connection: ConnectionState,
     accent_light2_color: Option<egui::Color32>,
     accent_color: Option<egui::Color32>,
     foreground_color: Option<egui::Color32>,
     window_info: WindowRegistryInfo,
     options: Options,
-    change_workspace: fn(usize, usize),
+    provider: Arc<dyn WorkspaceProvider>,
 }
 
 impl SwitcherWindowView {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         window: Arc<Window>,
         host: HWND,
         taskbar: Taskbar,
         proxy: EventLoopProxy<AppMessage>,
         window_info: WindowRegistryInfo,
-        monitor_state: crate::state::Monitor,
+        monitor_state: switcher_core::state::Monitor,
+        state: &State,
+        connection: ConnectionState,
         options: Options,
-        change_workspace: fn(usize, usize),
+        provider: Arc<dyn WorkspaceProvider>,
     ) -> anyhow::Result<Self> {
         let mut view = Self {
             window,
@@ -112,13 +145,18 @@ impl SwitcherWindowView {
             proxy,
             taskbar,
             monitor_state,
-            context_menu: Self::create_context_menu()?,
+            binding_modes: state.binding_modes.clone(),
+            paused: state.paused,
+            connection,
+            context_menu: Self::create_context_menu(provider.as_ref(), state.paused)?,
+            workspace_menu: None,
+            drag: DragState::default(),
             accent_color: None,
             accent_light2_color: None,
             foreground_color: None,
             window_info,
             options,
-            change_workspace,
+            provider,
         };
 
         if let Err(e) = view.update_system_colors() {
@@ -128,17 +166,116 @@ impl SwitcherWindowView {
         Ok(view)
     }
 
-    fn create_context_menu() -> anyhow::Result<ContextMenuState> {
+    fn create_context_menu(
+        provider: &dyn WorkspaceProvider,
+        paused: bool,
+    ) -> anyhow::Result<ContextMenuState> {
         let quit = MenuItem::new("Quit", true, None);
         let move_resize = MenuItem::new("Move && Resize", true, None);
-        let menu = Menu::with_items(&[&move_resize, &quit])?;
+        let pause = provider.supports_pause().then(|| {
+            MenuItem::new(
+                crate::utils::pause_menu_text(provider.name(), paused),
+                true,
+                None,
+            )
+        });
+        let menu = Menu::with_items(&[&move_resize])?;
+        if let Some(pause) = &pause {
+            menu.append(pause)?;
+        }
+
+        let commands = provider
+            .menu_commands()
+            .iter()
+            .map(|c| (MenuItem::new(c.label, true, None), c.command))
+            .collect::<Vec<_>>();
+        let submenu = if commands.is_empty() {
+            None
+        } else {
+            let submenu = Submenu::new(provider.name(), true);
+            for (item, _) in &commands {
+                submenu.append(item)?;
+            }
+            menu.append(&submenu)?;
+            Some(submenu)
+        };
+
+        menu.append(&quit)?;
+
         Ok(ContextMenuState {
             menu,
             quit,
             move_resize,
+            pause,
+            submenu,
+            commands,
+        })
+    }
+
+    /// Builds the menu of a single workspace: its actions followed by the
+    /// items of the global context menu.
+    fn create_workspace_menu(&self, workspace_name: &str) -> anyhow::Result<WorkspaceMenuState> {
+        let actions = self
+            .provider
+            .workspace_actions()
+            .iter()
+            .map(|&action| (MenuItem::new(action.label(), true, None), action))
+            .collect::<Vec<_>>();
+
+        let menu = Menu::new();
+        for (item, _) in &actions {
+            menu.append(item)?;
+        }
+        menu.append(&PredefinedMenuItem::separator())?;
+
+        let global = &self.context_menu;
+        menu.append(&global.move_resize)?;
+        if let Some(pause) = &global.pause {
+            menu.append(pause)?;
+        }
+        if let Some(submenu) = &global.submenu {
+            menu.append(submenu)?;
+        }
+        menu.append(&global.quit)?;
+
+        Ok(WorkspaceMenuState {
+            menu,
+            workspace: workspace_name.to_string(),
+            actions,
         })
     }
 
+    /// Runs a window manager command, telling the user when it fails.
+    fn run_menu_command(&self, command: &str) {
+        if let Err(e) = self.provider.run_command(command) {
+            tracing::warn!("Failed to run `{command}`: {e}");
+
+            let message = format!("{} command `{command}` failed: {e}", self.provider.name());
+            std::thread::spawn(move || crate::error_dialog(message));
+        }
+    }
+
+    /// Runs a workspace action, telling the user when it fails.
+    fn run_workspace_action(&self, workspace_name: &str, action: WorkspaceAction) {
+        let monitor_idx = self.monitor_state.index;
+        let focus_monitor = self.options.focus_monitor_on_switch;
+        if let Err(e) =
+            self.provider
+                .run_workspace_action(monitor_idx, workspace_name, action, focus_monitor)
+        {
+            tracing::warn!(
+                "Failed to run {:?} on workspace {workspace_name:?}: {e}",
+                action.label()
+            );
+
+            let message = format!(
+                "{} on workspace {workspace_name:?} failed: {e}",
+                action.label()
+            );
+            std::thread::spawn(move || crate::error_dialog(message));
+        }
+    }
+
     fn show_context_menu(&self) {
         tracing::debug!("Showing context menu");
 
@@ -150,6 +287,21 @@ impl SwitcherWindowView {
         };
     }
 
+    fn show_workspace_menu(&mut self, workspace_name: &str) {
+        tracing::debug!("Showing context menu of workspace {workspace_name:?}");
+
+        let workspace_menu = match self.create_workspace_menu(workspace_name) {
+            Ok(menu) => self.workspace_menu.insert(menu),
+            Err(e) => {
+                tracing::error!("Failed to create the workspace menu: {e}");
+                return self.show_context_menu();
+            }
+        };
+
+        let hwnd = self.host.0 as isize;
+        unsafe { workspace_menu.menu.show_context_menu_for_hwnd(hwnd, None) };
+    }
+
     fn update_system_colors(&mut self) -> anyhow::Result<()> {
         let settings = UISettings::new()?;
 
@@ -274,86 +426,190 @@ impl SwitcherWindowView {
         }
     }
 
-    fn workspaces_row(&mut self, ui: &mut egui::Ui) -> egui::Response {
-        // show context menu on right click
-        if ui.input(|i| i.pointer.button_pressed(egui::PointerButton::Secondary)) {
-            self.show_context_menu();
+    fn activate_workspace(&self, workspace_name: &str) {
+        let monitor_idx = self.monitor_state.index;
+        let focus_monitor = self.options.focus_monitor_on_switch;
+        if let Err(e) = self
+            .provider
+            .activate_workspace(monitor_idx, workspace_name, focus_monitor)
+        {
+            tracing::warn!("Failed to activate workspace {workspace_name:?}: {e}");
         }
+    }
 
-        ui.horizontal_centered(|ui| {
-            ui.scope(|ui| {
-                ui.style_mut().spacing.item_spacing = egui::vec2(4., 4.);
-
-                // Optionally enable scroll switching
-                if self.options.enable_scroll_switching {
-                    let delta = ui.input(|i| i.raw_scroll_delta.y);
-                    if delta != 0.0 {
-                        let count = self.monitor_state.workspaces.len();
-                        if count > 0 {
-                            let focused_idx = self
-                                .monitor_state
-                                .workspaces
-                                .iter()
-                                .position(|w| w.focused)
-                                .unwrap_or(0);
-                            let next_idx = if delta > 0.0 {
-                                // scroll up -> previous
-                                focused_idx.checked_sub(1).unwrap_or(count - 1)
-                            } else {
-                                // scroll down -> next
-                                (focused_idx + 1) % count
-                            };
-                            (self.change_workspace)(self.monitor_state.index, next_idx);
+    /// Runs the action bound to a click on the button of `workspace_name`.
+    fn run_mouse_binding(&self, workspace_name: &str, trigger: MouseTrigger) {
+        match self.options.mouse_bindings.action(trigger) {
+            Some(WorkspaceAction::Focus) => self.activate_workspace(workspace_name),
+            Some(action) => self.run_workspace_action(workspace_name, action),
+            None => tracing::trace!("No action bound to {trigger}"),
+        }
+    }
+
+    /// Feeds the cursor to the drag state while a window is dragged, since
+    /// the switcher gets no pointer input then.
+    fn follow_window_drag(&mut self, ctx: &egui::Context) {
+        let mut point = POINT::default();
+        let mapped = unsafe {
+            GetCursorPos(&mut point).is_ok() && ScreenToClient(self.host, &mut point).as_bool()
+        };
+        if mapped {
+            let ppp = ctx.pixels_per_point();
+            let pos = egui::pos2(point.x as f32, point.y as f32) / ppp;
+            self.drag.window_moved(pos);
+        }
+
+        ctx.request_repaint();
+    }
+
+    /// Asks the app to move a workspace dropped at `pos` outside the switcher.
+    fn drop_workspace(&self, workspace: String, pos: egui::Pos2, ppp: f32) -> anyhow::Result<()> {
+        let mut point = POINT {
+            x: (pos.x * ppp) as i32,
+            y: (pos.y * ppp) as i32,
+        };
+        if !unsafe { ClientToScreen(self.host, &mut point) }.as_bool() {
+            anyhow::bail!("Failed to map the drop position to the screen");
+        }
+
+        self.proxy.send_event(AppMessage::DropWorkspace {
+            monitor_idx: self.monitor_state.index,
+            workspace,
+            x: point.x,
+            y: point.y,
+        })?;
+
+        Ok(())
+    }
+
+    fn workspaces_row(&mut self, ui: &mut egui::Ui) -> egui::Response {
+        // show a context menu on right click, the one of the workspace under
+        // the pointer if any
+        let secondary_pressed =
+            ui.input(|i| i.pointer.button_pressed(egui::PointerButton::Secondary));
+        let mut menu_workspace = None;
+
+        let response = ui
+            .horizontal_centered(|ui| {
+                ui.scope(|ui| {
+                    ui.style_mut().spacing.item_spacing = egui::vec2(4., 4.);
+
+                    // Dim the row while the window manager is paused
+                    if self.paused {
+                        ui.multiply_opacity(0.5);
+                    }
+
+                    // Optionally enable scroll switching
+                    if self.options.enable_scroll_switching {
+                        let delta = ui.input(|i| i.raw_scroll_delta.y);
+                        if let Some(target) = self.monitor_state.scroll_target(delta) {
+                            self.activate_workspace(&target.name);
                         }
                     }
-                }
 
-                let iter = self
-                    .monitor_state
-                    .workspaces
-                    .iter()
-                    .filter(|w| !(self.options.hide_empty_workspaces && w.is_empty));
-
-                let mut rendered_any = false;
-                for workspace in iter {
-                    let btn = WorkspaceButton::new(workspace)
-                        .dark_mode(Some(self.is_system_dark_mode()))
-                        .line_focused_color_opt(self.line_focused_color())
-                        .text_color_opt(self.foreground_color)
-                        .line_on_top(self.is_taskbar_on_top());
-
-                    if ui.add(btn).clicked() {
-                        (self.change_workspace)(self.monitor_state.index, workspace.index);
+                    let iter = self
+                        .monitor_state
+                        .workspaces
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, w)| !(self.options.hide_empty_workspaces && w.is_empty));
+
+                    let drop_target = self.drag.target().map(str::to_string);
+                    self.drag.begin_frame();
+
+                    for (index, workspace) in iter {
+                        let alias = self.options.workspace_aliases.get(&workspace.name);
+                        let btn = WorkspaceButton::new(workspace, &self.options.theme)
+                            .label(&self.options.label, index, alias.map(String::as_str))
+                            .dark_mode(Some(self.is_system_dark_mode()))
+                            .line_focused_color_opt(self.line_focused_color())
+                            .text_color_opt(self.foreground_color)
+                            .line_on_top(self.is_taskbar_on_top())
+                            .show_window_count(self.options.show_window_count)
+                            .show_tiling_direction()
+                            .drop_target(drop_target.as_deref() == Some(workspace.name.as_str()));
+
+                        let response = btn.show(ui);
+                        self.drag.button(ui, &response.button, &workspace.name);
+                        if secondary_pressed && response.button.hovered() {
+                            menu_workspace = Some(workspace.name.clone());
+                        }
+
+                        if response.tiling_direction.is_some_and(|r| r.clicked()) {
+                            let monitor_idx = self.monitor_state.index;
+                            if let Err(e) = self
+                                .provider
+                                .toggle_tiling_direction(monitor_idx, &workspace.name)
+                            {
+                                tracing::warn!("Failed to toggle the tiling direction: {e}");
+                            }
+                        } else if let Some(trigger) = MouseTrigger::from_response(&response.button)
+                        {
+                            self.run_mouse_binding(&workspace.name, trigger);
+                        }
+                    }
+
+                    self.drag.end_frame();
+
+                    for mode in &self.binding_modes {
+                        let pill = BindingModePill::new(mode)
+                            .dark_mode(Some(self.is_system_dark_mode()))
+                            .fill_color_opt(self.line_focused_color())
+                            .text_color_opt(self.foreground_color);
+
+                        if ui.add(pill).clicked() {
+                            if let Err(e) = self.provider.disable_binding_mode(&mode.name) {
+                                tracing::warn!(
+                                    "Failed to disable binding mode {:?}: {e}",
+                                    mode.name
+                                );
+                            }
+                        }
                     }
-                    rendered_any = true;
-                }
 
-                if !rendered_any && !self.options.hide_if_offline {
-                    // Show offline label subtly when no workspaces rendered
-                    let text = "GlazeWM Offline";
-                    let font_id = egui::FontId::default();
-                    let color = self
-                        .foreground_color
-                        .unwrap_or_else(|| if self.is_system_dark_mode() {
-                            egui::Color32::WHITE
-                        } else {
-                            egui::Color32::BLACK
+                    if !self.connection.is_connected() && !self.options.hide_if_offline {
+                        // Keep the last known workspaces and show the connection status next to them
+                        let text = self.connection.describe(self.provider.name());
+                        let font_id = egui::FontId::default();
+                        let color = self.foreground_color.unwrap_or_else(|| {
+                            if self.is_system_dark_mode() {
+                                egui::Color32::WHITE
+                            } else {
+                                egui::Color32::BLACK
+                            }
                         });
-                    let galley = ui.painter().layout_no_wrap(text.into(), font_id.clone(), color);
-                    let size = galley.rect.size();
-                    let (rect, _resp) =
-                        ui.allocate_exact_size(size + egui::vec2(16., 8.), egui::Sense::hover());
-                    ui.painter().text(
-                        rect.center(),
-                        egui::Align2::CENTER_CENTER,
-                        text,
-                        font_id,
-                        color,
-                    );
-                }
+                        let galley =
+                            ui.painter()
+                                .layout_no_wrap(text.clone(), font_id.clone(), color);
+                        let size = galley.rect.size();
+                        let (rect, _resp) = ui
+                            .allocate_exact_size(size + egui::vec2(16., 8.), egui::Sense::hover());
+                        ui.painter().text(
+                            rect.center(),
+                            egui::Align2::CENTER_CENTER,
+                            text,
+                            font_id,
+                            color,
+                        );
+                    }
+                })
             })
-        })
-        .response
+            .response;
+
+        if secondary_pressed {
+            match menu_workspace {
+                Some(name) => self.show_workspace_menu(&name),
+                None => self.show_context_menu(),
+            }
+        }
+
+        if let Some(DropAction::Workspace { workspace, pos }) = self.drag.take_drop() {
+            if let Err(e) = self.drop_workspace(workspace, pos, ui.ctx().pixels_per_point()) {
+                tracing::error!("Failed to drop workspace: {e}");
+            }
+        }
+
+        response
     }
 
     fn transparent_panel(&self, ctx: &egui::Context) -> egui::CentralPanel {
@@ -384,16 +640,61 @@ impl EguiView for SwitcherWindowView {
                     .find(|m| m.id == self.monitor_state.id)
                     .cloned()
                     .unwrap_or_default();
+                self.binding_modes = state.binding_modes.clone();
+                if self.paused != state.paused {
+                    self.paused = state.paused;
+                    if let Some(pause) = &self.context_menu.pause {
+                        let text = crate::utils::pause_menu_text(self.provider.name(), self.paused);
+                        pause.set_text(text);
+                    }
+                }
+            }
+
+            AppMessage::ConnectionChanged(connection) => self.connection = *connection,
+
+            AppMessage::OptionsChanged(options) => {
+                self.options = options.for_monitor(&self.monitor_state.id)
+            }
+
+            AppMessage::WindowDragStarted => self.follow_window_drag(ctx),
+
+            AppMessage::WindowDragEnded => {
+                if let Some(DropAction::FocusedWindow { workspace }) = self.drag.window_released() {
+                    self.run_workspace_action(&workspace, WorkspaceAction::MoveFocusedWindowHere);
+                }
             }
 
             AppMessage::MenuEvent(e) if e.id() == self.context_menu.move_resize.id() => {
                 self.start_host_dragging()?
             }
 
+            AppMessage::MenuEvent(e)
+                if self
+                    .context_menu
+                    .pause
+                    .as_ref()
+                    .is_some_and(|p| e.id() == p.id()) =>
+            {
+                self.proxy.send_event(AppMessage::TogglePause)?
+            }
+
             AppMessage::MenuEvent(e) if e.id() == self.context_menu.quit.id() => {
                 self.close_host()?
             }
 
+            AppMessage::MenuEvent(e) => {
+                let commands = &self.context_menu.commands;
+                if let Some((_, command)) = commands.iter().find(|(item, _)| item.id() == e.id()) {
+                    self.run_menu_command(command);
+                } else if let Some(menu) = &self.workspace_menu {
+                    let actions = &menu.actions;
+                    if let Some((_, action)) = actions.iter().find(|(item, _)| item.id() == e.id())
+                    {
+                        self.run_workspace_action(&menu.workspace, *action);
+                    }
+                }
+            }
+
             AppMessage::StartMoveResize(serial_number_id)
                 if serial_number_id == &self.monitor_state.id =>
             {
@@ -421,6 +722,10 @@ impl EguiView for SwitcherWindowView {
     }
 
     fn update(&mut self, ctx: &egui::Context) {
+        if self.drag.is_window_dragged() {
+            self.follow_window_drag(ctx);
+        }
+
         self.transparent_panel(ctx).show(ctx, |ui| {
             let response = self.workspaces_row(ui);
             if let Err(e) = self.resize_host_to_rect(response.rect, ctx.pixels_per_point()) {